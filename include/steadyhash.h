@@ -0,0 +1,41 @@
+/* C bindings for steadyhash, built with `cargo build --release --features steadyhash-ffi`. */
+
+#ifndef STEADYHASH_H
+#define STEADYHASH_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct SteadyhashHasher SteadyhashHasher;
+
+/* Creates a hasher for the algorithm called `name`, as accepted by `steadyhash --type`. A
+ * `bit_length` of 0 selects the default bit length of the algorithm, and `key` can be NULL if
+ * `key_len` is 0. Returns NULL on error, see steadyhash_last_error(). */
+SteadyhashHasher *steadyhash_hasher_new(const char *name, size_t bit_length, const uint8_t *key,
+                                        size_t key_len);
+
+/* Feeds `len` bytes at `data` to the hasher. */
+void steadyhash_hasher_update(SteadyhashHasher *hasher, const uint8_t *data, size_t len);
+
+/* Consumes the hasher and returns the checksum as a hex string, to be freed with
+ * steadyhash_string_free(). */
+char *steadyhash_hasher_finalize_hex(SteadyhashHasher *hasher);
+
+/* Frees a hasher without computing its checksum. */
+void steadyhash_hasher_free(SteadyhashHasher *hasher);
+
+/* Frees a string returned by steadyhash_hasher_finalize_hex(). */
+void steadyhash_string_free(char *s);
+
+/* Message of the last failed call on this thread, or NULL. Owned by the library. */
+const char *steadyhash_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* STEADYHASH_H */
@@ -0,0 +1,164 @@
+//! The `bench` subcommand, which measures how fast every algorithm is on this machine.
+
+use crate::input::{InputOptions, OpenedFile};
+use anyhow::Error;
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+use steadyhash::{
+    Checksum, HashOptions,
+    hashing::{Input, sp800_185::Sp800185Function},
+};
+
+/// Algorithms and bit lengths that get benchmarked, covering each distinct internal state size.
+const ALGORITHMS: &[(Checksum, usize)] = &[
+    (Checksum::Md5, 128),
+    (Checksum::Sha, 160),
+    (Checksum::Sha, 256),
+    (Checksum::Sha, 512),
+    (Checksum::Sha3, 256),
+    (Checksum::Sha3, 512),
+    (Checksum::Keccak, 256),
+    (Checksum::Shake128, 256),
+    (Checksum::Shake256, 512),
+    (Checksum::K12, 256),
+    (Checksum::Blake2b, 256),
+    (Checksum::Blake2b, 512),
+    (Checksum::Blake3, 256),
+    (Checksum::Sp800185(Sp800185Function::CShake128), 256),
+    (Checksum::Sp800185(Sp800185Function::Kmac256), 512),
+    (Checksum::Sp800185(Sp800185Function::TupleHash128), 256),
+    (Checksum::Whirlpool, 512),
+    (Checksum::Ripemd, 160),
+    (Checksum::Sm3, 256),
+    (Checksum::Streebog, 256),
+    (Checksum::Tiger, 192),
+    (Checksum::Skein256, 256),
+    (Checksum::Skein512, 512),
+    (Checksum::Skein1024, 1024),
+    (Checksum::Groestl, 256),
+    (Checksum::Groestl, 512),
+    (Checksum::Md4, 128),
+    (Checksum::Md2, 128),
+    (Checksum::Ed2k, 128),
+    (Checksum::Crc32, 32),
+    (Checksum::Crc32c, 32),
+    (Checksum::Crc64Xz, 64),
+    (Checksum::Cksum, 32),
+    (Checksum::BsdSum, 16),
+    (Checksum::SysvSum, 16),
+    (Checksum::Fnv1a, 64),
+    (Checksum::SipHash, 64),
+    (Checksum::HighwayHash, 64),
+    (Checksum::Murmur3, 128),
+];
+
+/// Minimum time spent hashing the in-memory buffer with each algorithm.
+const MIN_DURATION: Duration = Duration::from_millis(500);
+
+/// Hashes a buffer of `size` bytes, or the file at `file`, with every algorithm and prints the
+/// throughput of each one. Algorithms that can hash a single input on several threads are
+/// measured both on one thread and on `threads`.
+pub fn run(
+    size: usize,
+    file: Option<&Path>,
+    threads: usize,
+    input_options: &InputOptions,
+) -> Result<(), Error> {
+    let buffer = if file.is_none() {
+        pseudo_random_bytes(size)
+    } else {
+        Vec::new()
+    };
+
+    println!("{:<16} {:>7} {:>10}", "algorithm", "threads", "MB/s");
+
+    for &(checksum, bit_length) in ALGORITHMS {
+        let mut thread_counts = vec![1];
+        if checksum.supports_multithreading() && threads > 1 {
+            thread_counts.push(threads);
+        }
+
+        for threads in thread_counts {
+            let options = HashOptions {
+                bit_length,
+                key: checksum.is_keyed().then(|| vec![0x5a; key_size(checksum)]),
+                multithreaded: threads > 1,
+                ..Default::default()
+            };
+
+            let (bytes, elapsed) = match file {
+                Some(path) => {
+                    let start = Instant::now();
+                    let mut opened = OpenedFile::open(path, input_options)?;
+                    checksum.calculate(&options, opened.input())?;
+                    (fs::metadata(path)?.len(), start.elapsed())
+                }
+                None => {
+                    let start = Instant::now();
+                    let mut bytes = 0;
+                    while bytes == 0 || start.elapsed() < MIN_DURATION {
+                        checksum.calculate(&options, Input::Bytes(&buffer))?;
+                        bytes += buffer.len() as u64;
+                    }
+                    (bytes, start.elapsed())
+                }
+            };
+
+            let rate = bytes as f64 / elapsed.as_secs_f64() / 1_000_000.0;
+            println!(
+                "{:<16} {threads:>7} {rate:>10.1}",
+                checksum.bsd_name(bit_length).to_ascii_lowercase()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Size of the dummy key used for keyed algorithms, in bytes.
+const fn key_size(checksum: Checksum) -> usize {
+    match checksum {
+        Checksum::HighwayHash => 32,
+        _ => 16,
+    }
+}
+
+/// Fills a buffer with bytes from a xorshift generator, so that the input doesn't have any
+/// patterns that an algorithm could be faster on.
+fn pseudo_random_bytes(size: usize) -> Vec<u8> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_algorithm_runs() {
+        let buffer = pseudo_random_bytes(1024);
+
+        for &(checksum, bit_length) in ALGORITHMS {
+            let options = HashOptions {
+                bit_length,
+                key: checksum.is_keyed().then(|| vec![0x5a; key_size(checksum)]),
+                ..Default::default()
+            };
+            assert!(
+                checksum.calculate(&options, Input::Bytes(&buffer)).is_ok(),
+                "{checksum} failed"
+            );
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Recording the entries processed by a run with `--checkpoint`, so that it can be continued with
+//! `--resume` after an interruption.
+//!
+//! The checkpoint starts with a line describing the run, followed by a line per processed entry,
+//! holding the entry and its outputs, one per algorithm, separated by tabs. Backslashes, tabs and
+//! newlines are escaped.
+
+use anyhow::Error;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use steadyhash::errors::CheckpointError;
+
+/// Minimum time between two flushes of the checkpoint to the disk.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Outputs of the entries processed by an earlier run, by entry.
+pub type Resumed = HashMap<String, Vec<String>>;
+
+/// A checkpoint being written.
+pub struct Checkpoint {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    last_flush: Instant,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint at `path` for a run described by `run`. With `resume`, the entries
+    /// already in the checkpoint are returned and new ones are appended, otherwise it's started
+    /// over.
+    pub fn open(path: &Path, run: &str, resume: bool) -> Result<(Checkpoint, Resumed), Error> {
+        let mut resumed = Resumed::new();
+
+        let file = if resume && path.exists() {
+            let mut lines = BufReader::new(File::open(path)?).lines();
+
+            let found = lines.next().transpose()?.unwrap_or_default();
+            if found != run {
+                return Err(CheckpointError::DifferentRun {
+                    expected: run.to_owned(),
+                    found,
+                }
+                .into());
+            }
+
+            for (number, line) in lines.enumerate() {
+                let line = line?;
+                let mut fields = line.split('\t').map(unescape);
+                let entry = fields.next().unwrap_or_default();
+                let outputs: Vec<String> = fields.collect();
+                if outputs.is_empty() {
+                    return Err(CheckpointError::InvalidEntry(number + 2).into());
+                }
+                resumed.insert(entry, outputs);
+            }
+
+            OpenOptions::new().append(true).open(path)?
+        } else {
+            let mut file = File::create(path)?;
+            writeln!(file, "{run}")?;
+            file
+        };
+
+        let checkpoint = Checkpoint {
+            path: path.to_owned(),
+            writer: BufWriter::new(file),
+            last_flush: Instant::now(),
+        };
+        Ok((checkpoint, resumed))
+    }
+
+    /// Records that `entry` has been processed, producing `outputs`.
+    pub fn record<S: AsRef<str>>(&mut self, entry: &str, outputs: &[S]) -> Result<(), Error> {
+        write!(self.writer, "{}", escape(entry))?;
+        for output in outputs {
+            write!(self.writer, "\t{}", escape(output.as_ref()))?;
+        }
+        writeln!(self.writer)?;
+
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+
+        Ok(())
+    }
+
+    /// Removes the checkpoint once the run has completed.
+    pub fn finish(self) -> Result<(), Error> {
+        drop(self.writer);
+        fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_escape_round_trip() {
+        let s = "a\tb\\nc\nd";
+        assert_eq!(unescape(&escape(s)), s);
+        assert!(!escape(s).contains(['\t', '\n']));
+    }
+
+    #[test]
+    fn test_resume() {
+        let path = env::temp_dir().join(format!("steadyhash-checkpoint-{}", std::process::id()));
+
+        let (mut checkpoint, resumed) = Checkpoint::open(&path, "SHA256", false).unwrap();
+        assert!(resumed.is_empty());
+        checkpoint.record("a\tb", &["1234  a\tb", "5678"]).unwrap();
+        drop(checkpoint);
+
+        assert!(Checkpoint::open(&path, "MD5", true).is_err());
+
+        let (checkpoint, resumed) = Checkpoint::open(&path, "SHA256", true).unwrap();
+        assert_eq!(resumed["a\tb"], ["1234  a\tb", "5678"]);
+        checkpoint.finish().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,1061 @@
+//! Checksum algorithms and their parameters.
+
+use crate::{
+    errors::{ExternalHasherError, HashOptionsError, ParseChecksumError},
+    hashing::{
+        DEFAULT_BUFFER_SIZE, DynHasher, Hasher, Input,
+        blake2b::Blake2b,
+        blake3::Blake3Sum,
+        crc::{CksumSum, Crc32Sum, Crc32cSum, Crc64Sum, Crc64Variant},
+        ed2k::Ed2kSum,
+        external::ExternalHasher,
+        feed,
+        fnv::FnvSum,
+        groestl::GroestlSum,
+        highway::HighwaySum,
+        hmac::Hmac,
+        k12::K12Sum,
+        keccak::KeccakSum,
+        md2::Md2Sum,
+        md4::Md4Sum,
+        md5::Md5Sum,
+        murmur3::Murmur3Sum,
+        ripemd::RipemdSum,
+        sha3::{Sha3Sum, ShakeSum, ShakeVariant},
+        shasum::ShaSum,
+        siphash::SipHashSum,
+        skein::{SkeinSum, SkeinVariant},
+        sm3::Sm3Sum,
+        sp800_185::{Sp800185Function, Sp800185Sum, TUPLEHASH_MAX_INPUT},
+        streebog::StreebogSum,
+        sum::{BsdSum, SysvSum},
+        tiger::{TigerSum, TigerVariant},
+        whirlpool::WhirlpoolSum,
+    },
+};
+use anyhow::Error;
+use std::{fmt::Display, io::Read, str::FromStr};
+
+/// Parameters of a checksum, besides the algorithm itself.
+#[derive(Default, Clone)]
+pub struct HashOptions {
+    /// Bit length of the checksum
+    pub bit_length: usize,
+
+    /// Secret key for keyed algorithms
+    pub key: Option<Vec<u8>>,
+
+    /// Seed for seeded algorithms
+    pub seed: Option<u32>,
+
+    /// Whether to wrap the digest in HMAC, using `key`
+    pub hmac: bool,
+
+    /// Salt for algorithms that support one
+    pub salt: Option<Vec<u8>>,
+
+    /// Personalization string for algorithms that support one
+    pub personal: Option<Vec<u8>>,
+
+    /// Context string for key derivation
+    pub context: Option<String>,
+
+    /// Customization string for the SP 800-185 functions
+    pub customization: Option<String>,
+
+    /// Whether to hash each file on several threads, for algorithms that support it
+    pub multithreaded: bool,
+
+    /// Command computing the checksum, for [`Checksum::External`]
+    pub external: Option<ExternalHasher>,
+}
+
+/// A checksum algorithm.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Checksum {
+    Sha,
+    Sha3,
+    Md5,
+    Blake2b,
+    Crc32,
+    Crc32c,
+    Crc64Xz,
+    Crc64Ecma,
+    Shake128,
+    Shake256,
+    K12,
+    Whirlpool,
+    Ripemd,
+    Sm3,
+    Streebog,
+    Tiger,
+    Tiger2,
+    Md4,
+    Md2,
+    Keccak,
+    Fnv1a,
+    SipHash,
+    HighwayHash,
+    Murmur3,
+    Ed2k,
+    Skein256,
+    Skein512,
+    Skein1024,
+    Groestl,
+    Blake3,
+    Sp800185(Sp800185Function),
+    Cksum,
+    BsdSum,
+    SysvSum,
+
+    /// An algorithm implemented by a command, set in [`HashOptions::external`]
+    External,
+}
+
+impl Checksum {
+    /// Every built-in algorithm, in the order they're listed in.
+    pub const ALL: &[Checksum] = &[
+        Checksum::Sha,
+        Checksum::Sha3,
+        Checksum::Md5,
+        Checksum::Blake2b,
+        Checksum::Crc32,
+        Checksum::Crc32c,
+        Checksum::Crc64Xz,
+        Checksum::Crc64Ecma,
+        Checksum::Shake128,
+        Checksum::Shake256,
+        Checksum::K12,
+        Checksum::Whirlpool,
+        Checksum::Ripemd,
+        Checksum::Sm3,
+        Checksum::Streebog,
+        Checksum::Tiger,
+        Checksum::Tiger2,
+        Checksum::Md4,
+        Checksum::Md2,
+        Checksum::Keccak,
+        Checksum::Fnv1a,
+        Checksum::SipHash,
+        Checksum::HighwayHash,
+        Checksum::Murmur3,
+        Checksum::Ed2k,
+        Checksum::Skein256,
+        Checksum::Skein512,
+        Checksum::Skein1024,
+        Checksum::Groestl,
+        Checksum::Blake3,
+        Checksum::Sp800185(Sp800185Function::CShake128),
+        Checksum::Sp800185(Sp800185Function::CShake256),
+        Checksum::Sp800185(Sp800185Function::Kmac128),
+        Checksum::Sp800185(Sp800185Function::Kmac256),
+        Checksum::Sp800185(Sp800185Function::TupleHash128),
+        Checksum::Sp800185(Sp800185Function::TupleHash256),
+        Checksum::Cksum,
+        Checksum::BsdSum,
+        Checksum::SysvSum,
+    ];
+
+    /// Names the algorithm can be selected by with `--type`, case-insensitively. The first one is
+    /// its main name.
+    pub const fn names(self) -> &'static [&'static str] {
+        match self {
+            Checksum::Sha => &["sha"],
+            Checksum::Sha3 => &["sha3"],
+            Checksum::Md5 => &["md5"],
+            Checksum::Blake2b => &["blake2b", "blake", "b2", "blake2"],
+            Checksum::Crc32 => &["crc32", "crc"],
+            Checksum::Crc32c => &["crc32c"],
+            Checksum::Crc64Xz => &["crc64", "crc64-xz"],
+            Checksum::Crc64Ecma => &["crc64-ecma"],
+            Checksum::Shake128 => &["shake128"],
+            Checksum::Shake256 => &["shake256"],
+            Checksum::K12 => &["k12", "kangarootwelve"],
+            Checksum::Whirlpool => &["whirlpool"],
+            Checksum::Ripemd => &["ripemd", "rmd"],
+            Checksum::Sm3 => &["sm3"],
+            Checksum::Streebog => &["streebog", "gost"],
+            Checksum::Tiger => &["tiger"],
+            Checksum::Tiger2 => &["tiger2"],
+            Checksum::Md4 => &["md4"],
+            Checksum::Md2 => &["md2"],
+            Checksum::Keccak => &["keccak"],
+            Checksum::Fnv1a => &["fnv1a", "fnv"],
+            Checksum::SipHash => &["siphash", "siphash24"],
+            Checksum::HighwayHash => &["highway", "highwayhash"],
+            Checksum::Murmur3 => &["murmur3", "murmurhash3"],
+            Checksum::Ed2k => &["ed2k"],
+            Checksum::Skein256 => &["skein256"],
+            Checksum::Skein512 => &["skein512", "skein"],
+            Checksum::Skein1024 => &["skein1024"],
+            Checksum::Groestl => &["groestl", "grostl"],
+            Checksum::Blake3 => &["blake3", "b3"],
+            Checksum::Sp800185(Sp800185Function::CShake128) => &["cshake128"],
+            Checksum::Sp800185(Sp800185Function::CShake256) => &["cshake256"],
+            Checksum::Sp800185(Sp800185Function::Kmac128) => &["kmac128"],
+            Checksum::Sp800185(Sp800185Function::Kmac256) => &["kmac256"],
+            Checksum::Sp800185(Sp800185Function::TupleHash128) => &["tuplehash128"],
+            Checksum::Sp800185(Sp800185Function::TupleHash256) => &["tuplehash256"],
+            Checksum::Cksum => &["cksum"],
+            Checksum::BsdSum => &["bsdsum", "sum"],
+            Checksum::SysvSum => &["sysvsum"],
+            Checksum::External => &[],
+        }
+    }
+
+    /// Parses a name that may include the bit length, like `sha256`, `sha3-384`, `blake2b-512` or
+    /// `sha1`, as well as the names used in BSD-style output. Returns the bit length if there's
+    /// one.
+    pub fn parse_with_length(s: &str) -> Result<(Checksum, Option<usize>), ParseChecksumError> {
+        if let Ok(checksum) = s.parse() {
+            return Ok((checksum, None));
+        }
+        if s.eq_ignore_ascii_case("sha1") {
+            return Ok((Checksum::Sha, Some(160)));
+        }
+
+        let error = || ParseChecksumError { value: s.into() };
+        // the length is either after a dash, or right after the name
+        let (name, bits) = match s.rsplit_once('-') {
+            Some((name, bits)) if !bits.is_empty() && bits.bytes().all(|b| b.is_ascii_digit()) => {
+                (name, bits)
+            }
+            _ => s.split_at(s.trim_end_matches(|c: char| c.is_ascii_digit()).len()),
+        };
+        let checksum: Checksum = name.parse().map_err(|_| error())?;
+        let bit_length: usize = bits.parse().map_err(|_| error())?;
+
+        // fixed-length algorithms would otherwise silently ignore the length
+        let bit_lengths = checksum.bit_lengths();
+        if !bit_lengths.is_empty() && !bit_lengths.contains(&bit_length) {
+            return Err(error());
+        }
+
+        Ok((checksum, Some(bit_length)))
+    }
+
+    /// Bit lengths the algorithm supports. An empty list means any non-zero multiple of 8.
+    pub const fn bit_lengths(self) -> &'static [usize] {
+        match self {
+            Checksum::Sha => ShaSum::VALID_VALUES,
+            Checksum::Sha3 => Sha3Sum::VALID_VALUES,
+            Checksum::Md5 => Md5Sum::VALID_VALUES,
+            Checksum::Blake2b => Blake2b::VALID_VALUES,
+            Checksum::Crc32 => Crc32Sum::VALID_VALUES,
+            Checksum::Crc32c => Crc32cSum::VALID_VALUES,
+            Checksum::Crc64Xz | Checksum::Crc64Ecma => Crc64Sum::VALID_VALUES,
+            Checksum::Shake128 | Checksum::Shake256 => ShakeSum::VALID_VALUES,
+            Checksum::K12 => K12Sum::VALID_VALUES,
+            Checksum::Whirlpool => WhirlpoolSum::VALID_VALUES,
+            Checksum::Ripemd => RipemdSum::VALID_VALUES,
+            Checksum::Sm3 => Sm3Sum::VALID_VALUES,
+            Checksum::Streebog => StreebogSum::VALID_VALUES,
+            Checksum::Tiger | Checksum::Tiger2 => TigerSum::VALID_VALUES,
+            Checksum::Md4 => Md4Sum::VALID_VALUES,
+            Checksum::Md2 => Md2Sum::VALID_VALUES,
+            Checksum::Keccak => KeccakSum::VALID_VALUES,
+            Checksum::Fnv1a => FnvSum::VALID_VALUES,
+            Checksum::SipHash => SipHashSum::VALID_VALUES,
+            Checksum::HighwayHash => HighwaySum::VALID_VALUES,
+            Checksum::Murmur3 => Murmur3Sum::VALID_VALUES,
+            Checksum::Ed2k => Ed2kSum::VALID_VALUES,
+            Checksum::Skein256 => SkeinVariant::Skein256.valid_values(),
+            Checksum::Skein512 => SkeinVariant::Skein512.valid_values(),
+            Checksum::Skein1024 => SkeinVariant::Skein1024.valid_values(),
+            Checksum::Groestl => GroestlSum::VALID_VALUES,
+            Checksum::Blake3 => Blake3Sum::VALID_VALUES,
+            Checksum::Sp800185(_) => Sp800185Sum::VALID_VALUES,
+            Checksum::Cksum => CksumSum::VALID_VALUES,
+            Checksum::BsdSum => BsdSum::VALID_VALUES,
+            Checksum::SysvSum => SysvSum::VALID_VALUES,
+            Checksum::External => &[],
+        }
+    }
+
+    /// Bit length used when none is given.
+    pub const fn default_bits(self) -> usize {
+        match self {
+            Checksum::BsdSum | Checksum::SysvSum => 16,
+            Checksum::Md5 | Checksum::Md4 | Checksum::Md2 | Checksum::Ed2k => 128,
+            Checksum::Whirlpool => 512,
+            Checksum::Ripemd => 160,
+            Checksum::Tiger | Checksum::Tiger2 => 192,
+            Checksum::Crc32 | Checksum::Crc32c | Checksum::Cksum => 32,
+            Checksum::Murmur3 => 128,
+            Checksum::Crc64Xz
+            | Checksum::Crc64Ecma
+            | Checksum::Fnv1a
+            | Checksum::SipHash
+            | Checksum::HighwayHash => 64,
+            Checksum::Shake256 | Checksum::Skein512 => 512,
+            Checksum::Sp800185(
+                Sp800185Function::CShake256
+                | Sp800185Function::Kmac256
+                | Sp800185Function::TupleHash256,
+            ) => 512,
+            Checksum::Skein1024 => 1024,
+            // sensible default
+            _ => 256,
+        }
+    }
+
+    /// Guesses the algorithm of an untagged digest of `bit_length` bits. Those of coreutils (SHA-2,
+    /// SHA-1 and MD5) are preferred, so a 512-bit digest is taken as SHA-512 rather than BLAKE2b.
+    pub const fn guess(bit_length: usize) -> Option<Checksum> {
+        match bit_length {
+            32 => Some(Checksum::Crc32),
+            128 => Some(Checksum::Md5),
+            160 | 224 | 256 | 384 | 512 => Some(Checksum::Sha),
+            _ => None,
+        }
+    }
+
+    /// Whether the algorithm is only kept around for compatibility with legacy manifests.
+    pub const fn is_legacy(self) -> bool {
+        matches!(self, Checksum::Md4 | Checksum::Md2)
+    }
+
+    /// Whether the algorithm needs a secret key.
+    pub const fn is_keyed(self) -> bool {
+        match self {
+            Checksum::SipHash | Checksum::HighwayHash => true,
+            Checksum::Sp800185(function) => function.is_keyed(),
+            _ => false,
+        }
+    }
+
+    /// Whether the algorithm can take a secret key, even if it doesn't require one.
+    pub const fn accepts_key(self) -> bool {
+        self.is_keyed() || matches!(self, Checksum::Blake2b | Checksum::Blake3)
+    }
+
+    /// Whether the algorithm takes a salt and personalization string.
+    pub const fn accepts_salt(self) -> bool {
+        matches!(self, Checksum::Blake2b)
+    }
+
+    /// Whether the algorithm can hash a single file on several threads.
+    pub const fn supports_multithreading(self) -> bool {
+        matches!(self, Checksum::Blake3)
+    }
+
+    /// Largest input the algorithm should be used on, for those which keep their whole input in
+    /// memory rather than a running state, like TupleHash.
+    pub const fn max_input_size(self) -> Option<u64> {
+        match self {
+            Checksum::Sp800185(Sp800185Function::TupleHash128 | Sp800185Function::TupleHash256) => {
+                Some(TUPLEHASH_MAX_INPUT)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the algorithm takes a seed.
+    pub const fn is_seeded(self) -> bool {
+        matches!(self, Checksum::Murmur3)
+    }
+
+    /// Block size of the digest in bytes, used by HMAC. Returns `None` for algorithms that aren't
+    /// cryptographic digests, and therefore can't be used with HMAC.
+    pub const fn hmac_block_size(self, bit_length: usize) -> Option<usize> {
+        Some(match self {
+            Checksum::Md2 => 16,
+            Checksum::Sha => match bit_length {
+                384 | 512 => 128,
+                _ => 64,
+            },
+            Checksum::Groestl => match bit_length {
+                384 | 512 => 128,
+                _ => 64,
+            },
+            // sponge constructions use their rate
+            Checksum::Sha3 | Checksum::Keccak => 200 - 2 * (bit_length / 8),
+            Checksum::Shake128 | Checksum::K12 => 168,
+            Checksum::Shake256 => 136,
+            Checksum::Blake2b => 128,
+            Checksum::Skein256 => 32,
+            Checksum::Skein512 => 64,
+            Checksum::Skein1024 => 128,
+            Checksum::Md5
+            | Checksum::Md4
+            | Checksum::Whirlpool
+            | Checksum::Ripemd
+            | Checksum::Sm3
+            | Checksum::Streebog
+            | Checksum::Tiger
+            | Checksum::Tiger2
+            | Checksum::Blake3 => 64,
+            _ => return None,
+        })
+    }
+
+    /// Number of whitespace-separated fields the checksum is made of, as some formats (like `cksum`
+    /// and `sum`) also print the size of the data.
+    pub const fn digest_fields(self) -> usize {
+        match self {
+            Checksum::Cksum | Checksum::BsdSum | Checksum::SysvSum => 2,
+            _ => 1,
+        }
+    }
+
+    /// Separator between the checksum and the file name in the default output style.
+    pub const fn separator(self) -> &'static str {
+        match self {
+            // POSIX cksum and sum use a single space
+            Checksum::Cksum | Checksum::BsdSum | Checksum::SysvSum => " ",
+            // coreutils' *sum tools use two spaces
+            _ => "  ",
+        }
+    }
+
+    /// Creates a hasher for the algorithm, given the bit length, key, seed and HMAC mode in
+    /// `options`.
+    pub fn hasher(&self, options: &HashOptions) -> Result<Box<dyn DynHasher>, Error> {
+        let bit_length = options.bit_length;
+
+        if options.hmac {
+            self.validate_bit_length(bit_length)?;
+            let block_size = self
+                .hmac_block_size(bit_length)
+                .ok_or_else(|| HashOptionsError::HmacUnsupported(self.to_string()))?;
+            let key = options
+                .key
+                .as_deref()
+                .ok_or_else(|| HashOptionsError::MissingKey(format!("HMAC-{self}")))?;
+
+            // the digest itself is computed without any of the other parameters, so they're
+            // rejected rather than silently ignored
+            let name = || format!("HMAC-{self}");
+            if options.salt.is_some() || options.personal.is_some() {
+                return Err(HashOptionsError::UnexpectedSaltOrPersonal(name()).into());
+            }
+            if options.context.is_some() {
+                return Err(HashOptionsError::UnexpectedContext(name()).into());
+            }
+            if options.customization.is_some() {
+                return Err(HashOptionsError::UnexpectedCustomization(name()).into());
+            }
+            if options.seed.is_some() {
+                return Err(HashOptionsError::UnexpectedSeed(name()).into());
+            }
+
+            let inner = HashOptions {
+                bit_length,
+                ..Default::default()
+            };
+            let hmac = Hmac::new(block_size, key, || self.build_hasher(&inner))?;
+            return Ok(Box::new(hmac));
+        }
+
+        self.validate(options)?;
+        self.build_hasher(options)
+    }
+
+    /// Checks that the parameters in `options` are accepted by the algorithm, outside of HMAC.
+    fn validate(&self, options: &HashOptions) -> Result<(), Error> {
+        self.validate_bit_length(options.bit_length)?;
+        if self.is_keyed() && options.key.is_none() {
+            return Err(HashOptionsError::MissingKey(self.to_string()).into());
+        }
+        if !self.accepts_key() && options.key.is_some() {
+            return Err(HashOptionsError::UnexpectedKey(self.to_string()).into());
+        }
+        if !self.accepts_salt() && (options.salt.is_some() || options.personal.is_some()) {
+            return Err(HashOptionsError::UnexpectedSaltOrPersonal(self.to_string()).into());
+        }
+        if !matches!(self, Checksum::Sp800185(_)) && options.customization.is_some() {
+            return Err(HashOptionsError::UnexpectedCustomization(self.to_string()).into());
+        }
+        if !matches!(self, Checksum::Blake3) && options.context.is_some() {
+            return Err(HashOptionsError::UnexpectedContext(self.to_string()).into());
+        }
+        if !self.is_seeded() && options.seed.is_some() {
+            return Err(HashOptionsError::UnexpectedSeed(self.to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the algorithm supports `bit_length`, as fixed-length algorithms would otherwise
+    /// silently ignore it.
+    fn validate_bit_length(&self, bit_length: usize) -> Result<(), HashOptionsError> {
+        let bit_lengths = self.bit_lengths();
+        if !bit_lengths.is_empty() && !bit_lengths.contains(&bit_length) {
+            return Err(HashOptionsError::UnsupportedBitLength {
+                name: self.to_string(),
+                bit_length,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Hashes `input`, given the bit length, key, seed and HMAC mode in `options`.
+    pub fn calculate(&self, options: &HashOptions, input: Input) -> Result<String, Error> {
+        // external commands are given the whole input, as they can't be driven incrementally
+        if *self == Checksum::External && !options.hmac {
+            self.validate(options)?;
+            let external = options
+                .external
+                .as_ref()
+                .ok_or(ExternalHasherError::MissingCommand)?;
+            return external.run(input);
+        }
+
+        let mut hasher = self.hasher(options)?;
+        feed(input, |data| hasher.update(data))?;
+        Ok(hasher.finalize())
+    }
+
+    /// Creates a hasher for the algorithm, after the parameters in `options` have been validated.
+    fn build_hasher(&self, options: &HashOptions) -> Result<Box<dyn DynHasher>, Error> {
+        let bit_length = options.bit_length;
+        let key = options.key.as_deref().unwrap_or_default();
+        let seed = options.seed.unwrap_or_default();
+
+        Ok(match self {
+            Checksum::Sha => Box::new(ShaSum::new(bit_length)?),
+            Checksum::Blake2b => Box::new(
+                Blake2b::new(bit_length)?
+                    .with_key(key)?
+                    .with_salt(options.salt.as_deref().unwrap_or_default())?
+                    .with_personal(options.personal.as_deref().unwrap_or_default())?,
+            ),
+            // bit length gets ignored
+            Checksum::Md5 => Box::new(Md5Sum::new()),
+            Checksum::Sha3 => Box::new(Sha3Sum::new(bit_length)?),
+            Checksum::Crc32 => Box::new(Crc32Sum::new()),
+            Checksum::Crc32c => Box::new(Crc32cSum::new()),
+            Checksum::Crc64Xz => Box::new(Crc64Sum::new(Crc64Variant::Xz)),
+            Checksum::Crc64Ecma => Box::new(Crc64Sum::new(Crc64Variant::Ecma)),
+            Checksum::Shake128 => Box::new(ShakeSum::new(ShakeVariant::Shake128, bit_length)?),
+            Checksum::Shake256 => Box::new(ShakeSum::new(ShakeVariant::Shake256, bit_length)?),
+            Checksum::K12 => Box::new(K12Sum::new(bit_length)?),
+            // fixed 512-bit output, bit length gets ignored
+            Checksum::Whirlpool => Box::new(WhirlpoolSum::new()),
+            Checksum::Ripemd => Box::new(RipemdSum::new(bit_length)?),
+            // fixed 256-bit output, bit length gets ignored
+            Checksum::Sm3 => Box::new(Sm3Sum::new()),
+            Checksum::Streebog => Box::new(StreebogSum::new(bit_length)?),
+            // fixed 192-bit output, bit length gets ignored
+            Checksum::Tiger => Box::new(TigerSum::new(TigerVariant::Tiger)),
+            Checksum::Tiger2 => Box::new(TigerSum::new(TigerVariant::Tiger2)),
+            Checksum::Md4 => Box::new(Md4Sum::new()),
+            Checksum::Md2 => Box::new(Md2Sum::new()),
+            Checksum::Keccak => Box::new(KeccakSum::new(bit_length)?),
+            Checksum::Fnv1a => Box::new(FnvSum::new(bit_length)?),
+            Checksum::SipHash => Box::new(SipHashSum::new(bit_length, key)?),
+            Checksum::HighwayHash => Box::new(HighwaySum::new(bit_length, key)?),
+            Checksum::Murmur3 => Box::new(Murmur3Sum::new(bit_length, seed)?),
+            Checksum::Ed2k => Box::new(Ed2kSum::new()),
+            Checksum::Skein256 => Box::new(SkeinSum::new(SkeinVariant::Skein256, bit_length)?),
+            Checksum::Skein512 => Box::new(SkeinSum::new(SkeinVariant::Skein512, bit_length)?),
+            Checksum::Skein1024 => Box::new(SkeinSum::new(SkeinVariant::Skein1024, bit_length)?),
+            Checksum::Groestl => Box::new(GroestlSum::new(bit_length)?),
+            Checksum::Blake3 => {
+                let hasher = Blake3Sum::new(bit_length)?.with_multithreading(options.multithreaded);
+                let hasher = match (&options.key, &options.context) {
+                    (Some(key), _) => hasher.with_key(key)?,
+                    (_, Some(context)) => hasher.with_derive_key(context),
+                    (None, None) => hasher,
+                };
+                Box::new(hasher)
+            }
+            Checksum::Sp800185(function) => Box::new(
+                Sp800185Sum::new(*function, bit_length)?
+                    .with_key(key)
+                    .with_customization(
+                        options
+                            .customization
+                            .as_deref()
+                            .unwrap_or_default()
+                            .as_bytes(),
+                    ),
+            ),
+            Checksum::Cksum => Box::new(CksumSum::new()),
+            Checksum::BsdSum => Box::new(BsdSum::new()),
+            Checksum::SysvSum => Box::new(SysvSum::new()),
+            Checksum::External => {
+                return Err(ExternalHasherError::NotIncremental(self.to_string()).into());
+            }
+        })
+    }
+
+    /// Name of the algorithm with its bit length, as used in BSD-style output. The algorithms of
+    /// coreutils are named as its `--tag` option names them.
+    pub fn bsd_name(&self, bit_length: usize) -> String {
+        match self {
+            Checksum::Sha => {
+                if bit_length == 160 {
+                    "SHA1".into()
+                } else {
+                    format!("SHA{}", bit_length)
+                }
+            }
+            Checksum::Sha3 => format!("SHA3-{}", bit_length),
+            // b2sum leaves out the default length
+            Checksum::Blake2b if bit_length == 512 => "BLAKE2b".into(),
+            Checksum::Blake2b => format!("BLAKE2b-{}", bit_length),
+            Checksum::Md5 => "MD5".into(),
+            Checksum::Crc32 => "CRC32".into(),
+            Checksum::Crc32c => "CRC32C".into(),
+            Checksum::Crc64Xz => "CRC64".into(),
+            Checksum::Crc64Ecma => "CRC64-ECMA".into(),
+            Checksum::Shake128 => format!("SHAKE128-{}", bit_length),
+            Checksum::Shake256 => format!("SHAKE256-{}", bit_length),
+            Checksum::K12 => format!("K12-{}", bit_length),
+            Checksum::Whirlpool => "WHIRLPOOL".into(),
+            Checksum::Ripemd => format!("RIPEMD{}", bit_length),
+            Checksum::Sm3 => "SM3".into(),
+            Checksum::Streebog => format!("STREEBOG{}", bit_length),
+            Checksum::Tiger => "TIGER".into(),
+            Checksum::Tiger2 => "TIGER2".into(),
+            Checksum::Md4 => "MD4".into(),
+            Checksum::Md2 => "MD2".into(),
+            Checksum::Keccak => format!("KECCAK-{}", bit_length),
+            Checksum::Fnv1a => format!("FNV1A-{}", bit_length),
+            Checksum::SipHash => format!("SIPHASH24-{}", bit_length),
+            Checksum::HighwayHash => format!("HIGHWAYHASH-{}", bit_length),
+            Checksum::Murmur3 => format!("MURMUR3-{}", bit_length),
+            Checksum::Ed2k => "ED2K".into(),
+            Checksum::Skein256 => format!("SKEIN256-{}", bit_length),
+            Checksum::Skein512 => format!("SKEIN512-{}", bit_length),
+            Checksum::Skein1024 => format!("SKEIN1024-{}", bit_length),
+            Checksum::Groestl => format!("GROESTL-{}", bit_length),
+            Checksum::Blake3 => "BLAKE3".into(),
+            Checksum::Sp800185(function) => format!("{}-{}", function_name(*function), bit_length),
+            Checksum::Cksum => "CKSUM".into(),
+            Checksum::BsdSum => "BSDSUM".into(),
+            Checksum::SysvSum => "SYSVSUM".into(),
+            Checksum::External => "EXTERNAL".into(),
+        }
+    }
+
+    /// Name of the algorithm as computed with `options`, including the HMAC construction.
+    pub fn full_name(&self, options: &HashOptions) -> String {
+        if let (Checksum::External, Some(external)) = (self, &options.external) {
+            return external.name.clone();
+        }
+
+        let prefix = if options.hmac { "HMAC-" } else { "" };
+        format!("{prefix}{}", self.bsd_name(options.bit_length))
+    }
+
+    /// Code of the algorithm in the multicodec table, which tags its multihashes, if it has one.
+    /// Keyed, seeded and customized checksums have none, as they can't be recomputed from the
+    /// data alone.
+    pub fn multicodec(&self, options: &HashOptions) -> Option<u64> {
+        let parameterized = options.hmac
+            || options.key.is_some()
+            || options.seed.is_some()
+            || options.salt.is_some()
+            || options.personal.is_some()
+            || options.context.is_some()
+            || options.customization.is_some();
+        if parameterized {
+            return None;
+        }
+
+        let bytes = options.bit_length as u64 / 8;
+        Some(match (self, options.bit_length) {
+            (Checksum::Sha, 160) => 0x11,
+            (Checksum::Sha, 224) => 0x1013,
+            (Checksum::Sha, 256) => 0x12,
+            (Checksum::Sha, 384) => 0x20,
+            (Checksum::Sha, 512) => 0x13,
+            (Checksum::Sha3, 224) => 0x17,
+            (Checksum::Sha3, 256) => 0x16,
+            (Checksum::Sha3, 384) => 0x15,
+            (Checksum::Sha3, 512) => 0x14,
+            (Checksum::Keccak, 224) => 0x1a,
+            (Checksum::Keccak, 256) => 0x1b,
+            (Checksum::Keccak, 384) => 0x1c,
+            (Checksum::Keccak, 512) => 0x1d,
+            (Checksum::Shake128, _) => 0x18,
+            (Checksum::Shake256, _) => 0x19,
+            (Checksum::Blake3, _) => 0x1e,
+            (Checksum::K12, _) => 0x1d01,
+            // the BLAKE2b and Skein codes are numbered by output length in bytes
+            (Checksum::Blake2b, _) => 0xb200 + bytes,
+            (Checksum::Skein256, _) => 0xb300 + bytes,
+            (Checksum::Skein512, _) => 0xb320 + bytes,
+            (Checksum::Skein1024, _) => 0xb360 + bytes,
+            (Checksum::Md4, _) => 0xd4,
+            (Checksum::Md5, _) => 0xd5,
+            (Checksum::Ripemd, 160) => 0x1053,
+            (Checksum::Ripemd, 256) => 0x1054,
+            (Checksum::Ripemd, 320) => 0x1055,
+            (Checksum::Sm3, _) => 0x534d,
+            (Checksum::Murmur3, 32) => 0x23,
+            (Checksum::Crc32, _) => 0x0132,
+            (Checksum::Crc64Ecma, _) => 0x0164,
+            _ => return None,
+        })
+    }
+}
+
+/// Computes a checksum incrementally, from data fed in as many pieces as needed.
+///
+/// ```
+/// use steadyhash::{Checksum, ChecksumBuilder};
+///
+/// let checksum = ChecksumBuilder::new(Checksum::Sha3)?
+///     .update(b"hello ")
+///     .update(b"world")
+///     .finalize_hex();
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct ChecksumBuilder {
+    hasher: Box<dyn DynHasher>,
+}
+
+impl ChecksumBuilder {
+    /// Starts computing a checksum with the algorithm's default bit length and no other
+    /// parameters.
+    pub fn new(checksum: Checksum) -> Result<ChecksumBuilder, Error> {
+        let options = HashOptions {
+            bit_length: checksum.default_bits(),
+            ..Default::default()
+        };
+        ChecksumBuilder::with_options(checksum, &options)
+    }
+
+    /// Starts computing a checksum with the parameters in `options`, which are validated the same
+    /// way as by [`Checksum::calculate`].
+    pub fn with_options(
+        checksum: Checksum,
+        options: &HashOptions,
+    ) -> Result<ChecksumBuilder, Error> {
+        Ok(ChecksumBuilder {
+            hasher: checksum.hasher(options)?,
+        })
+    }
+
+    /// Feeds more data to the checksum.
+    pub fn update(mut self, data: &[u8]) -> ChecksumBuilder {
+        self.hasher.update(data);
+        self
+    }
+
+    /// Consumes the builder, returning the checksum as a hex string.
+    pub fn finalize_hex(self) -> String {
+        self.hasher.finalize()
+    }
+}
+
+/// Hashes everything read from `reader`, through a buffer of the default size.
+pub fn hash_reader(
+    checksum: Checksum,
+    options: &HashOptions,
+    mut reader: impl Read,
+) -> Result<String, Error> {
+    checksum.calculate(
+        options,
+        Input::Reader {
+            reader: &mut reader,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+        },
+    )
+}
+
+/// Hashes `input` with every algorithm of `algorithms` in a single pass, returning the checksums
+/// in the same order.
+///
+/// External hashers can only be used on their own, since they need the whole input.
+pub fn calculate_all(
+    algorithms: &[(Checksum, HashOptions)],
+    input: Input,
+) -> Result<Vec<String>, Error> {
+    if let [(checksum, options)] = algorithms {
+        return Ok(vec![checksum.calculate(options, input)?]);
+    }
+
+    let mut hashers = algorithms
+        .iter()
+        .map(|(checksum, options)| checksum.hasher(options))
+        .collect::<Result<Vec<_>, _>>()?;
+    feed(input, |data| {
+        for hasher in &mut hashers {
+            hasher.update(data);
+        }
+    })?;
+    Ok(hashers
+        .into_iter()
+        .map(|hasher| hasher.finalize())
+        .collect())
+}
+
+/// Name of an SP 800-185 function, as used in BSD-style output.
+const fn function_name(function: Sp800185Function) -> &'static str {
+    match function {
+        Sp800185Function::CShake128 => "CSHAKE128",
+        Sp800185Function::CShake256 => "CSHAKE256",
+        Sp800185Function::Kmac128 => "KMAC128",
+        Sp800185Function::Kmac256 => "KMAC256",
+        Sp800185Function::TupleHash128 => "TUPLEHASH128",
+        Sp800185Function::TupleHash256 => "TUPLEHASH256",
+    }
+}
+
+impl Display for Checksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Checksum::Blake2b => write!(f, "BLAKE2b"),
+            Checksum::Md5 => write!(f, "MD5"),
+            Checksum::Sha => write!(f, "SHA"),
+            Checksum::Sha3 => write!(f, "SHA3"),
+            Checksum::Crc32 => write!(f, "CRC32"),
+            Checksum::Crc32c => write!(f, "CRC32C"),
+            Checksum::Crc64Xz => write!(f, "CRC64/XZ"),
+            Checksum::Crc64Ecma => write!(f, "CRC64/ECMA-182"),
+            Checksum::Shake128 => write!(f, "SHAKE128"),
+            Checksum::Shake256 => write!(f, "SHAKE256"),
+            Checksum::K12 => write!(f, "KangarooTwelve"),
+            Checksum::Whirlpool => write!(f, "Whirlpool"),
+            Checksum::Ripemd => write!(f, "RIPEMD"),
+            Checksum::Sm3 => write!(f, "SM3"),
+            Checksum::Streebog => write!(f, "Streebog"),
+            Checksum::Tiger => write!(f, "Tiger"),
+            Checksum::Tiger2 => write!(f, "Tiger2"),
+            Checksum::Md4 => write!(f, "MD4"),
+            Checksum::Md2 => write!(f, "MD2"),
+            Checksum::Keccak => write!(f, "Keccak"),
+            Checksum::Fnv1a => write!(f, "FNV-1a"),
+            Checksum::SipHash => write!(f, "SipHash-2-4"),
+            Checksum::HighwayHash => write!(f, "HighwayHash"),
+            Checksum::Murmur3 => write!(f, "MurmurHash3"),
+            Checksum::Ed2k => write!(f, "eD2k"),
+            Checksum::Skein256 => write!(f, "Skein-256"),
+            Checksum::Skein512 => write!(f, "Skein-512"),
+            Checksum::Skein1024 => write!(f, "Skein-1024"),
+            Checksum::Groestl => write!(f, "Groestl"),
+            Checksum::Blake3 => write!(f, "BLAKE3"),
+            Checksum::Sp800185(function) => write!(f, "{}", function_name(*function)),
+            Checksum::Cksum => write!(f, "cksum"),
+            Checksum::BsdSum => write!(f, "BSD sum"),
+            Checksum::SysvSum => write!(f, "SysV sum"),
+            Checksum::External => write!(f, "external"),
+        }
+    }
+}
+
+/// Checksums are serialized as their main name, as accepted by `--type`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Checksum {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.names().first() {
+            Some(name) => serializer.serialize_str(name),
+            None => serializer.serialize_str("external"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Checksum {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for Checksum {
+    type Err = ParseChecksumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Checksum::ALL
+            .iter()
+            .find(|checksum| {
+                checksum
+                    .names()
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(s))
+            })
+            .copied()
+            .ok_or_else(|| ParseChecksumError { value: s.into() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_from_str() {
+        // Test with lowercase
+        assert_eq!(Checksum::from_str("sha").unwrap(), Checksum::Sha);
+        assert_eq!(Checksum::from_str("blake2b").unwrap(), Checksum::Blake2b);
+        assert_eq!(Checksum::from_str("md5").unwrap(), Checksum::Md5);
+        assert_eq!(Checksum::from_str("sha3").unwrap(), Checksum::Sha3);
+        assert_eq!(Checksum::from_str("crc32").unwrap(), Checksum::Crc32);
+        assert_eq!(Checksum::from_str("crc32c").unwrap(), Checksum::Crc32c);
+        assert_eq!(Checksum::from_str("crc64").unwrap(), Checksum::Crc64Xz);
+        assert_eq!(
+            Checksum::from_str("crc64-ecma").unwrap(),
+            Checksum::Crc64Ecma
+        );
+        assert_eq!(Checksum::from_str("shake128").unwrap(), Checksum::Shake128);
+
+        // Test with uppercase
+        assert_eq!(Checksum::from_str("SHa").unwrap(), Checksum::Sha);
+        assert_eq!(Checksum::from_str("BLake2b").unwrap(), Checksum::Blake2b);
+        assert_eq!(Checksum::from_str("mD5").unwrap(), Checksum::Md5);
+        assert_eq!(Checksum::from_str("sHA3").unwrap(), Checksum::Sha3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn checksum_deserialize() {
+        use serde::{Deserialize, de::IntoDeserializer, de::value::Error};
+
+        let deserialize = |name: &str| Checksum::deserialize(name.into_deserializer());
+        assert_eq!(deserialize("b3").unwrap(), Checksum::Blake3);
+        assert_eq!(
+            deserialize("kmac256").unwrap(),
+            Checksum::Sp800185(Sp800185Function::Kmac256)
+        );
+        let error: Result<Checksum, Error> = deserialize("nope");
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn checksum_parse_with_length() {
+        let parse = |s: &str| Checksum::parse_with_length(s).unwrap();
+        assert_eq!(parse("sha256"), (Checksum::Sha, Some(256)));
+        assert_eq!(parse("SHA1"), (Checksum::Sha, Some(160)));
+        assert_eq!(parse("sha3-384"), (Checksum::Sha3, Some(384)));
+        assert_eq!(parse("blake2b-512"), (Checksum::Blake2b, Some(512)));
+        assert_eq!(parse("ripemd160"), (Checksum::Ripemd, Some(160)));
+        assert_eq!(parse("crc64-ecma"), (Checksum::Crc64Ecma, None));
+        assert_eq!(parse("md5"), (Checksum::Md5, None));
+        assert!(Checksum::parse_with_length("md5-256").is_err());
+        assert!(Checksum::parse_with_length("sha-").is_err());
+        assert!(Checksum::parse_with_length("nope256").is_err());
+
+        // the names of BSD-style lines parse back to the same algorithm
+        for &checksum in Checksum::ALL {
+            let name = checksum.bsd_name(checksum.default_bits());
+            assert_eq!(parse(&name).0, checksum, "{name}");
+        }
+        assert_eq!(Checksum::Blake2b.bsd_name(512), "BLAKE2b");
+        assert_eq!(Checksum::Blake2b.bsd_name(256), "BLAKE2b-256");
+    }
+
+    #[test]
+    fn checksum_guess() {
+        assert_eq!(Checksum::guess(128), Some(Checksum::Md5));
+        assert_eq!(Checksum::guess(160), Some(Checksum::Sha));
+        assert_eq!(Checksum::guess(512), Some(Checksum::Sha));
+        assert_eq!(Checksum::guess(200), None);
+    }
+
+    #[test]
+    fn builder_matches_calculate() {
+        let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+        let options = HashOptions {
+            bit_length: 256,
+            key: Some(b"key".to_vec()),
+            hmac: true,
+            ..Default::default()
+        };
+        let expected = Checksum::Sha
+            .calculate(&options, Input::Bytes(&data))
+            .unwrap();
+
+        let mut builder = ChecksumBuilder::with_options(Checksum::Sha, &options).unwrap();
+        for chunk in data.chunks(7) {
+            builder = builder.update(chunk);
+        }
+        assert_eq!(builder.finalize_hex(), expected);
+        assert_eq!(
+            hash_reader(Checksum::Sha, &options, data.as_slice()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_bit_lengths() {
+        let options = |bit_length| HashOptions {
+            bit_length,
+            ..Default::default()
+        };
+        assert!(Checksum::Md5.hasher(&options(128)).is_ok());
+        for (checksum, bit_length) in [
+            (Checksum::Md5, 256),
+            (Checksum::Md5, 7),
+            (Checksum::Crc32, 999),
+        ] {
+            let err = checksum.hasher(&options(bit_length)).err().unwrap();
+            assert!(err.downcast_ref::<HashOptionsError>().is_some(), "{err}");
+        }
+        let hmac = HashOptions {
+            key: Some(b"key".to_vec()),
+            hmac: true,
+            ..options(256)
+        };
+        assert!(Checksum::Md5.hasher(&hmac).is_err());
+        assert!(Checksum::Blake3.hasher(&options(1024)).is_ok());
+    }
+
+    #[test]
+    fn hmac_rejects_other_parameters() {
+        let hmac = HashOptions {
+            bit_length: 512,
+            key: Some(b"key".to_vec()),
+            hmac: true,
+            ..Default::default()
+        };
+        assert!(Checksum::Blake2b.hasher(&hmac).is_ok());
+
+        let rejected = [
+            HashOptions {
+                salt: Some(b"salt".to_vec()),
+                ..hmac.clone()
+            },
+            HashOptions {
+                personal: Some(b"personal".to_vec()),
+                ..hmac.clone()
+            },
+            HashOptions {
+                context: Some("context".into()),
+                ..hmac.clone()
+            },
+            HashOptions {
+                customization: Some("customization".into()),
+                ..hmac.clone()
+            },
+            HashOptions {
+                seed: Some(1),
+                ..hmac.clone()
+            },
+        ];
+        for options in rejected {
+            let err = Checksum::Blake2b.hasher(&options).err().unwrap();
+            assert!(err.downcast_ref::<HashOptionsError>().is_some(), "{err}");
+        }
+    }
+
+    #[test]
+    fn calculate_all_matches_calculate() {
+        let algorithms = [
+            (
+                Checksum::Sha,
+                HashOptions {
+                    bit_length: 256,
+                    ..Default::default()
+                },
+            ),
+            (
+                Checksum::Md5,
+                HashOptions {
+                    bit_length: 128,
+                    ..Default::default()
+                },
+            ),
+        ];
+        let checksums = calculate_all(&algorithms, Input::Bytes(b"abc")).unwrap();
+        for ((checksum, options), actual) in algorithms.iter().zip(&checksums) {
+            let expected = checksum.calculate(options, Input::Bytes(b"abc")).unwrap();
+            assert_eq!(*actual, expected);
+        }
+    }
+}
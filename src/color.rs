@@ -0,0 +1,74 @@
+//! Colors of the results printed in check mode.
+
+use clap::ValueEnum;
+use std::{
+    borrow::Cow,
+    env,
+    io::{self, IsTerminal},
+};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[1;31m";
+const RESET: &str = "\x1b[0m";
+
+/// When to color the results.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    /// When stdout is a terminal, unless `NO_COLOR` is set
+    #[default]
+    Auto,
+
+    /// Always, even when stdout is redirected
+    Always,
+
+    /// Never
+    Never,
+}
+
+impl ColorMode {
+    /// Whether the results printed on stdout should be colored.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Auto => {
+                io::stdout().is_terminal()
+                    && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            }
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+/// Colors the status at the end of a check result like `foo.bar: OK`, green if the file is intact
+/// and red otherwise. Other lines are returned unchanged.
+pub fn paint(line: &str) -> Cow<'_, str> {
+    let statuses = [
+        (": OK", GREEN),
+        (": FAILED", RED),
+        (": FAILED open or read", RED),
+    ];
+    for (status, color) in statuses {
+        if let Some(path) = line.strip_suffix(status) {
+            return format!("{path}: {color}{}{RESET}", &status[2..]).into();
+        }
+    }
+
+    line.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(paint("foo.bar: OK"), "foo.bar: \x1b[32mOK\x1b[0m");
+        assert_eq!(paint("a: OK: FAILED"), "a: OK: \x1b[1;31mFAILED\x1b[0m");
+        assert_eq!(
+            paint("a: FAILED open or read"),
+            "a: \x1b[1;31mFAILED open or read\x1b[0m"
+        );
+        assert_eq!(paint("foo.bar: unknown"), "foo.bar: unknown");
+        assert!(!ColorMode::Never.enabled());
+    }
+}
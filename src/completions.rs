@@ -0,0 +1,91 @@
+//! The `completions` subcommand, which prints completion scripts for the supported shells.
+
+use crate::Args;
+use clap::{
+    Arg, Command, CommandFactory,
+    builder::{PossibleValue, StringValueParser, TypedValueParser},
+};
+use clap_complete::Shell;
+use std::{ffi::OsStr, io};
+use steadyhash::Checksum;
+
+/// Value parser for `--type`. It accepts any name, as external hashers can be registered, but
+/// offers the built-in algorithms to completion scripts.
+#[derive(Clone)]
+pub struct ChecksumTypeParser;
+
+impl TypedValueParser for ChecksumTypeParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<String, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(type_names().into_iter().map(PossibleValue::new)))
+    }
+}
+
+/// Names to complete `--type` with: the names of every algorithm, and the main names followed by
+/// the bit lengths, like `sha256` or `blake2b-512`.
+fn type_names() -> Vec<String> {
+    let mut names = Vec::new();
+    for &checksum in Checksum::ALL {
+        let Some((&main, _)) = checksum.names().split_first() else {
+            continue;
+        };
+        names.extend(checksum.names().iter().map(|&name| name.to_owned()));
+
+        // only the default and the largest of long lists, like the 64 lengths of BLAKE2b
+        let bit_lengths = match checksum.bit_lengths() {
+            [_] | [] => continue,
+            bit_lengths if bit_lengths.len() > 8 => {
+                vec![checksum.default_bits(), bit_lengths[bit_lengths.len() - 1]]
+            }
+            bit_lengths => bit_lengths.to_vec(),
+        };
+        for bits in bit_lengths {
+            names.push(match checksum {
+                Checksum::Sha if bits == 160 => "sha1".to_owned(),
+                Checksum::Sha => format!("sha{bits}"),
+                _ => format!("{main}-{bits}"),
+            });
+        }
+    }
+
+    names
+}
+
+/// Prints the completion script for `shell`.
+pub fn print(shell: Shell) {
+    clap_complete::generate(shell, &mut Args::command(), "steadyhash", &mut io::stdout());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_names() {
+        let names = type_names();
+        for name in [
+            "sha",
+            "sha1",
+            "sha256",
+            "sha3-512",
+            "blake2b-512",
+            "b3",
+            "crc32",
+        ] {
+            assert!(names.iter().any(|other| other == name), "{name}");
+        }
+        for name in &names {
+            assert!(Checksum::parse_with_length(name).is_ok(), "{name}");
+        }
+    }
+}
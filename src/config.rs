@@ -0,0 +1,191 @@
+//! Defaults read from the configuration file and from `STEADYHASH_*` environment variables. The
+//! environment takes precedence over the file, and options on the command line over both.
+
+use crate::color::ColorMode;
+use anyhow::{Context, Error};
+use clap::ValueEnum;
+use std::{env, fs, io::ErrorKind, path::PathBuf};
+use steadyhash::errors::ConfigError;
+use toml::{Table, Value};
+
+/// Environment variable overriding the path of the configuration file.
+const CONFIG_VAR: &str = "STEADYHASH_CONFIG";
+
+/// Defaults for options which weren't given on the command line.
+#[derive(Default, Debug, PartialEq)]
+pub struct Defaults {
+    /// Algorithm used when `--type` isn't given
+    pub checksum_type: Option<String>,
+
+    /// Bit length of the default algorithm
+    pub bit_length: Option<usize>,
+
+    /// Whether to create BSD-style checksums
+    pub bsd: Option<bool>,
+
+    /// Number of files hashed at once
+    pub jobs: Option<usize>,
+
+    /// When to color the results of check mode
+    pub color: Option<ColorMode>,
+}
+
+impl Defaults {
+    /// Reads the configuration file, if there's one, and the environment.
+    pub fn load() -> Result<Defaults, Error> {
+        let mut defaults = match config_path() {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(contents) => Defaults::from_toml(&contents)
+                    .with_context(|| format!("invalid configuration file {}", path.display()))?,
+                Err(e) if e.kind() == ErrorKind::NotFound => Defaults::default(),
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!("failed to read configuration file {}", path.display())
+                    });
+                }
+            },
+            None => Defaults::default(),
+        };
+        defaults.apply_env(|name| env::var(name).ok())?;
+
+        Ok(defaults)
+    }
+
+    /// Parses a configuration file, whose settings are named after the long options.
+    fn from_toml(contents: &str) -> Result<Defaults, Error> {
+        let table: Table = contents.parse()?;
+        let mut defaults = Defaults::default();
+
+        for (name, value) in table {
+            let invalid = |expected| ConfigError::InvalidValue {
+                name: name.clone(),
+                expected,
+            };
+            match name.as_str() {
+                "type" => {
+                    let Value::String(checksum_type) = value else {
+                        return Err(invalid("a string").into());
+                    };
+                    defaults.checksum_type = Some(checksum_type);
+                }
+                "length" => {
+                    defaults.bit_length = Some(integer(&value).ok_or_else(|| invalid("a number"))?);
+                }
+                "bsd" => {
+                    defaults.bsd = Some(value.as_bool().ok_or_else(|| invalid("true or false"))?);
+                }
+                "jobs" => defaults.jobs = Some(integer(&value).ok_or_else(|| invalid("a number"))?),
+                "color" => {
+                    let color = value
+                        .as_str()
+                        .and_then(|color| ColorMode::from_str(color, true).ok());
+                    defaults.color = Some(color.ok_or_else(|| invalid("auto, always or never"))?);
+                }
+                _ => return Err(ConfigError::UnknownSetting(name).into()),
+            }
+        }
+
+        Ok(defaults)
+    }
+
+    /// Overrides the settings with the `STEADYHASH_*` variables read through `var`.
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        let invalid = |name: &str, expected| ConfigError::InvalidValue {
+            name: format!("${name}"),
+            expected,
+        };
+
+        if let Some(checksum_type) = var("STEADYHASH_TYPE") {
+            self.checksum_type = Some(checksum_type);
+        }
+        if let Some(bit_length) = var("STEADYHASH_LENGTH") {
+            let bit_length = bit_length.trim().parse();
+            self.bit_length =
+                Some(bit_length.map_err(|_| invalid("STEADYHASH_LENGTH", "a number"))?);
+        }
+        if let Some(bsd) = var("STEADYHASH_BSD") {
+            self.bsd = Some(match bsd.trim() {
+                "1" | "true" => true,
+                "0" | "false" => false,
+                _ => return Err(invalid("STEADYHASH_BSD", "true or false")),
+            });
+        }
+        if let Some(jobs) = var("STEADYHASH_JOBS") {
+            let jobs = jobs.trim().parse();
+            self.jobs = Some(jobs.map_err(|_| invalid("STEADYHASH_JOBS", "a number"))?);
+        }
+        if let Some(color) = var("STEADYHASH_COLOR") {
+            let color = ColorMode::from_str(color.trim(), true);
+            self.color =
+                Some(color.map_err(|_| invalid("STEADYHASH_COLOR", "auto, always or never"))?);
+        }
+
+        Ok(())
+    }
+}
+
+fn integer(value: &Value) -> Option<usize> {
+    value.as_integer().and_then(|n| usize::try_from(n).ok())
+}
+
+/// Path of the configuration file: `$STEADYHASH_CONFIG`, or `steadyhash/config.toml` in the
+/// user's configuration directory.
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_VAR) {
+        return Some(path.into());
+    }
+
+    #[cfg(windows)]
+    let dir = env::var_os("APPDATA").map(PathBuf::from);
+    #[cfg(not(windows))]
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    dir.map(|dir| dir.join("steadyhash").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let defaults = Defaults::from_toml("type = \"sha3-512\"\nbsd = true\njobs = 4\n").unwrap();
+        assert_eq!(
+            defaults,
+            Defaults {
+                checksum_type: Some("sha3-512".into()),
+                bit_length: None,
+                bsd: Some(true),
+                jobs: Some(4),
+                color: None,
+            }
+        );
+
+        assert!(Defaults::from_toml("typo = \"md5\"").is_err());
+        assert!(Defaults::from_toml("jobs = -1").is_err());
+        assert!(Defaults::from_toml("bsd = \"yes\"").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let mut defaults = Defaults::from_toml("type = \"md5\"\nbsd = true").unwrap();
+        defaults
+            .apply_env(|name| match name {
+                "STEADYHASH_TYPE" => Some("blake3".into()),
+                "STEADYHASH_BSD" => Some("0".into()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(defaults.checksum_type.as_deref(), Some("blake3"));
+        assert_eq!(defaults.bsd, Some(false));
+
+        assert!(
+            defaults
+                .apply_env(|name| (name == "STEADYHASH_JOBS").then(|| "many".into()))
+                .is_err()
+        );
+    }
+}
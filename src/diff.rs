@@ -0,0 +1,167 @@
+//! The `diff` subcommand, which compares two manifests.
+
+use crate::is_sfv;
+use anyhow::{Context, Error};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+use steadyhash::{
+    Checksum,
+    manifest::{self, Clearsign},
+};
+
+/// A difference between two manifests.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    /// A file only listed in the new manifest
+    Added(String),
+
+    /// A file only listed in the old manifest
+    Removed(String),
+
+    /// A file listed in both, with different checksums
+    Changed(String),
+
+    /// A file of the old manifest listed under another path in the new one, with the same checksum
+    Renamed { from: String, to: String },
+}
+
+/// Formats the change like `git diff --name-status`, e.g. `M\tfoo.bar`.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added(path) => write!(f, "A\t{path}"),
+            Change::Removed(path) => write!(f, "D\t{path}"),
+            Change::Changed(path) => write!(f, "M\t{path}"),
+            Change::Renamed { from, to } => write!(f, "R\t{from}\t{to}"),
+        }
+    }
+}
+
+/// Reads the paths and checksums listed in the manifest at `path`, in order. Only the first entry
+/// of each path is kept.
+pub fn read_manifest(path: &Path, checksum: Checksum) -> Result<Vec<(String, String)>, Error> {
+    let file =
+        File::open(path).with_context(|| format!("failed to open manifest {}", path.display()))?;
+    let sfv = is_sfv(path);
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    let mut clearsign = Clearsign::default();
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let Some(line) = clearsign.strip(&line) else {
+            continue;
+        };
+        let entry = if sfv {
+            manifest::parse_sfv_line(line)
+        } else {
+            manifest::parse_line(checksum, line)
+        };
+        if let Some(entry) = entry
+            && seen.insert(entry.path.to_string())
+        {
+            entries.push((entry.path.into_owned(), entry.expected.to_ascii_lowercase()));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Compares the entries of two manifests. Removed and changed files come in the order of `old`,
+/// followed by the added files in the order of `new`. With `renames`, a removed file and an added
+/// one with the same checksum are reported as a rename.
+pub fn diff(old: &[(String, String)], new: &[(String, String)], renames: bool) -> Vec<Change> {
+    let old_paths: HashMap<&str, &str> = old
+        .iter()
+        .map(|(path, digest)| (path.as_str(), digest.as_str()))
+        .collect();
+    let new_paths: HashMap<&str, &str> = new
+        .iter()
+        .map(|(path, digest)| (path.as_str(), digest.as_str()))
+        .collect();
+
+    // added files by checksum, which removed files are matched against
+    let mut added: HashMap<&str, Vec<&str>> = HashMap::new();
+    if renames {
+        for (path, digest) in new.iter().rev() {
+            if !old_paths.contains_key(path.as_str()) {
+                added.entry(digest).or_default().push(path);
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    let mut renamed = HashSet::new();
+    for (path, digest) in old {
+        match new_paths.get(path.as_str()) {
+            Some(new_digest) if new_digest != digest => changes.push(Change::Changed(path.clone())),
+            Some(_) => {}
+            None => match added.get_mut(digest.as_str()).and_then(Vec::pop) {
+                Some(to) => {
+                    renamed.insert(to);
+                    changes.push(Change::Renamed {
+                        from: path.clone(),
+                        to: to.to_owned(),
+                    });
+                }
+                None => changes.push(Change::Removed(path.clone())),
+            },
+        }
+    }
+    for (path, _) in new {
+        if !old_paths.contains_key(path.as_str()) && !renamed.contains(path.as_str()) {
+            changes.push(Change::Added(path.clone()));
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|&(path, digest)| (path.to_owned(), digest.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = entries(&[("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")]);
+        let new = entries(&[("a", "1"), ("b", "5"), ("e", "3"), ("f", "6")]);
+
+        assert_eq!(
+            diff(&old, &new, false),
+            [
+                Change::Changed("b".into()),
+                Change::Removed("c".into()),
+                Change::Removed("d".into()),
+                Change::Added("e".into()),
+                Change::Added("f".into()),
+            ]
+        );
+        let changes = diff(&old, &new, true);
+        assert_eq!(
+            changes,
+            [
+                Change::Changed("b".into()),
+                Change::Renamed {
+                    from: "c".into(),
+                    to: "e".into()
+                },
+                Change::Removed("d".into()),
+                Change::Added("f".into()),
+            ]
+        );
+        assert_eq!(changes[1].to_string(), "R\tc\te");
+        assert!(diff(&old, &old, true).is_empty());
+    }
+}
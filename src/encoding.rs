@@ -0,0 +1,188 @@
+//! Encodings checksums can be printed and checked in, besides hex.
+//!
+//! ```
+//! use steadyhash::encoding::Encoding;
+//!
+//! let md5 = "900150983cd24fb0d6963f7d28e17f72";
+//! assert_eq!(Encoding::Base64.encode(md5), "kAFQmDzST7DWlj99KOF/cg==");
+//! assert_eq!(Encoding::Base64.to_hex("kAFQmDzST7DWlj99KOF/cg==").unwrap(), md5);
+//! ```
+
+use crate::errors::EncodingError;
+use data_encoding::{BASE32_NOPAD, BASE64_NOPAD, Encoding as DataEncoding};
+use std::{fmt, str::FromStr};
+
+/// How the bytes of a checksum are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Lowercase hexadecimal, as returned by [`Checksum::calculate`](crate::Checksum::calculate)
+    #[default]
+    Hex,
+
+    /// Standard base64 with padding (RFC 4648), as in HTTP `Digest` headers
+    Base64,
+
+    /// Standard base32 with padding (RFC 4648)
+    Base32,
+
+    /// Base58 with the Bitcoin alphabet
+    Base58,
+}
+
+impl Encoding {
+    /// Every encoding, in the order of their names.
+    pub const ALL: &[Encoding] = &[
+        Encoding::Hex,
+        Encoding::Base64,
+        Encoding::Base32,
+        Encoding::Base58,
+    ];
+
+    /// Name of the encoding, as accepted by `--encoding`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "base64",
+            Encoding::Base32 => "base32",
+            Encoding::Base58 => "base58",
+        }
+    }
+
+    /// Re-encodes a hex checksum. Checksums that aren't a single hex string, like the decimal
+    /// fields of `cksum`, are returned unchanged.
+    pub fn encode(self, hex: &str) -> String {
+        let Ok(bytes) = hex::decode(hex) else {
+            return hex.to_owned();
+        };
+
+        match self {
+            Encoding::Hex => hex.to_owned(),
+            Encoding::Base64 => data_encoding::BASE64.encode(&bytes),
+            Encoding::Base32 => data_encoding::BASE32.encode(&bytes),
+            Encoding::Base58 => bs58::encode(bytes).into_string(),
+        }
+    }
+
+    /// Encodes `bytes` as a multibase string: a prefix naming the encoding (`f`, `m`, `b` or `z`),
+    /// followed by the bytes in that encoding, without padding and in lowercase for base32.
+    pub fn multibase(self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Hex => format!("f{}", hex::encode(bytes)),
+            Encoding::Base64 => format!("m{}", BASE64_NOPAD.encode(bytes)),
+            Encoding::Base32 => format!("b{}", BASE32_NOPAD.encode(bytes).to_ascii_lowercase()),
+            Encoding::Base58 => format!("z{}", bs58::encode(bytes).into_string()),
+        }
+    }
+
+    /// Decodes a checksum written in this encoding back to lowercase hex. Padding is optional, as
+    /// are surrounding whitespace and the `0x` prefix of hex. Returns `None` if it isn't valid in
+    /// this encoding.
+    pub fn to_hex(self, encoded: &str) -> Option<String> {
+        let encoded = encoded.trim();
+        let bytes = match self {
+            Encoding::Hex => {
+                let unprefixed = encoded
+                    .strip_prefix("0x")
+                    .or_else(|| encoded.strip_prefix("0X"));
+                hex::decode(unprefixed.unwrap_or(encoded)).ok()?
+            }
+            Encoding::Base64 => decode_unpadded(&BASE64_NOPAD, encoded)?,
+            Encoding::Base32 => decode_unpadded(&BASE32_NOPAD, &encoded.to_ascii_uppercase())?,
+            Encoding::Base58 => bs58::decode(encoded).into_vec().ok()?,
+        };
+
+        Some(hex::encode(bytes))
+    }
+}
+
+/// Builds a multihash: the multicodec `code` of the algorithm and the length of `digest`, both as
+/// unsigned varints, followed by the digest itself.
+pub fn multihash(code: u64, digest: &[u8]) -> Vec<u8> {
+    let mut multihash = Vec::with_capacity(digest.len() + 4);
+    for mut n in [code, digest.len() as u64] {
+        while n >= 0x80 {
+            multihash.push(n as u8 | 0x80);
+            n >>= 7;
+        }
+        multihash.push(n as u8);
+    }
+    multihash.extend_from_slice(digest);
+    multihash
+}
+
+fn decode_unpadded(encoding: &DataEncoding, encoded: &str) -> Option<Vec<u8>> {
+    encoding
+        .decode(encoded.trim_end_matches('=').as_bytes())
+        .ok()
+}
+
+impl FromStr for Encoding {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Encoding::ALL
+            .iter()
+            .copied()
+            .find(|encoding| encoding.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| EncodingError::Unknown(s.to_owned()))
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA256_ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn test_round_trip() {
+        for &encoding in Encoding::ALL {
+            let encoded = encoding.encode(SHA256_ABC);
+            assert_eq!(encoding.to_hex(&encoded).unwrap(), SHA256_ABC, "{encoding}");
+        }
+        assert_eq!(Encoding::Base32.encode("352441c2"), "GUSEDQQ=");
+        assert_eq!(Encoding::Base58.encode("0001"), "12");
+    }
+
+    #[test]
+    fn test_lenient_decoding() {
+        assert_eq!(
+            Encoding::Base64
+                .to_hex("kAFQmDzST7DWlj99KOF/cg")
+                .unwrap()
+                .len(),
+            32
+        );
+        assert_eq!(Encoding::Base32.to_hex("gusedqq").unwrap(), "352441c2");
+        assert!(Encoding::Base64.to_hex("not base64!").is_none());
+        assert_eq!(Encoding::Hex.to_hex(" 0xABCD\t").unwrap(), "abcd");
+        assert!(Encoding::Hex.to_hex("0xyz").is_none());
+        // multi-field checksums are left alone
+        assert_eq!(Encoding::Base64.encode("123 45"), "123 45");
+    }
+
+    #[test]
+    fn test_multihash() {
+        let sha256 =
+            hex::decode("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+                .unwrap();
+        let multihash = multihash(0x12, &sha256);
+        assert_eq!(&multihash[..2], [0x12, 0x20]);
+        assert_eq!(
+            Encoding::Base58.multibase(&multihash),
+            "zQmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4"
+        );
+
+        // codes above 127 take several bytes
+        assert_eq!(
+            &super::multihash(0xb240, &[0; 64])[..4],
+            [0xc0, 0xe4, 0x02, 0x40]
+        );
+    }
+}
@@ -18,6 +18,335 @@ pub enum Sha3SumError {
     InvalidChecksumType(usize),
 }
 
+#[derive(Error, Debug)]
+pub enum GroestlSumError {
+    /// Error indicating that an invalid Groestl checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'Groestl-{0}'. The only supported types are Groestl-224, Groestl-256, Groestl-384 and Groestl-512"
+    )]
+    InvalidChecksumType(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum KeccakSumError {
+    /// Error indicating that an invalid Keccak checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'Keccak-{0}'. The only supported types are Keccak-224, Keccak-256, Keccak-384 and Keccak-512"
+    )]
+    InvalidChecksumType(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum ShakeSumError {
+    /// Error indicating that an invalid SHAKE output length has been provided.
+    #[error(
+        "Invalid output length '{0}' bits for SHAKE. The output length must be a non-zero multiple of 8"
+    )]
+    InvalidOutputLength(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum K12SumError {
+    /// Error indicating that an invalid KangarooTwelve output length has been provided.
+    #[error(
+        "Invalid output length '{0}' bits for KangarooTwelve. The output length must be a non-zero multiple of 8"
+    )]
+    InvalidOutputLength(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum RipemdSumError {
+    /// Error indicating that an invalid RIPEMD checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'RIPEMD-{0}'. Supported types are RIPEMD-160, RIPEMD-256 and RIPEMD-320"
+    )]
+    InvalidChecksumType(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum StreebogSumError {
+    /// Error indicating that an invalid Streebog checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'Streebog-{0}'. Supported types are Streebog-256 and Streebog-512"
+    )]
+    InvalidChecksumType(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum FnvSumError {
+    /// Error indicating that an invalid FNV-1a checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'FNV-1a-{0}'. Supported types are FNV-1a-32, FNV-1a-64 and FNV-1a-128"
+    )]
+    InvalidChecksumType(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum Murmur3SumError {
+    /// Error indicating that an invalid MurmurHash3 checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'MurmurHash3-{0}'. Supported types are MurmurHash3-32 (x86_32) and MurmurHash3-128 (x64_128)"
+    )]
+    InvalidChecksumType(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum SipHashSumError {
+    /// Error indicating that an invalid SipHash checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'SipHash-2-4-{0}'. Supported types are SipHash-2-4-64 and SipHash-2-4-128"
+    )]
+    InvalidChecksumType(usize),
+
+    /// Error indicating that the key doesn't have the size required by SipHash.
+    #[error("Invalid SipHash key length of {0} bytes. The key must be exactly 16 bytes long")]
+    InvalidKeyLength(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum HighwaySumError {
+    /// Error indicating that an invalid HighwayHash checksum type has been provided.
+    #[error(
+        "Invalid checksum type 'HighwayHash-{0}'. Supported types are HighwayHash-64, HighwayHash-128 and HighwayHash-256"
+    )]
+    InvalidChecksumType(usize),
+
+    /// Error indicating that the key doesn't have the size required by HighwayHash.
+    #[error("Invalid HighwayHash key length of {0} bytes. The key must be exactly 32 bytes long")]
+    InvalidKeyLength(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum EncodingError {
+    /// Error indicating that an unknown encoding was requested.
+    #[error("Unknown encoding '{0}'. Supported encodings are hex, base64, base32 and base58")]
+    Unknown(String),
+
+    /// Error indicating that a checksum isn't a string of bytes, like the fields of `cksum`.
+    #[error("{0} checksums aren't a string of bytes and can't be written raw or as multihashes")]
+    NotBinary(String),
+
+    /// Error indicating that an algorithm has no multicodec code to tag its multihashes with.
+    #[error("{0} has no multicodec code, so its checksums can't be written as multihashes")]
+    NoMulticodec(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    /// Error indicating that an SFV file was requested with another algorithm than CRC32.
+    #[error("SFV files only hold CRC32 checksums, not {0} checksums")]
+    NotSfvAlgorithm(String),
+}
+
+#[derive(Error, Debug)]
+pub enum SignatureError {
+    /// Error indicating that a public key isn't a minisign or signify Ed25519 public key.
+    #[error("Invalid public key. Expected a minisign or signify public key, or a file holding one")]
+    InvalidKey,
+
+    /// Error indicating that a signature file isn't a minisign or signify Ed25519 signature.
+    #[error("Invalid signature file {0}. Expected a minisign or signify signature")]
+    InvalidSignature(String),
+
+    /// Error indicating that a manifest was signed with another key than the given public key.
+    #[error("The manifest was signed with the key {signed}, not with the public key {expected}")]
+    KeyMismatch { expected: String, signed: String },
+
+    /// Error indicating that a signature doesn't match the manifest or its trusted comment.
+    #[error("The signature of {0} doesn't match, so its checksums can't be trusted")]
+    Mismatch(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ParseSizeError {
+    /// Error indicating that a size doesn't start with a number.
+    #[error(
+        "Invalid size '{0}'. Expected a number, optionally followed by a unit like K, MiB or GB"
+    )]
+    InvalidNumber(String),
+
+    /// Error indicating that a size has an unknown unit.
+    #[error(
+        "Invalid unit in size '{0}'. Supported units are B, K, M, G and T, as well as KiB, KB, etc."
+    )]
+    InvalidSuffix(String),
+
+    /// Error indicating that a size doesn't fit in 64 bits.
+    #[error("Size '{0}' is too large")]
+    TooLarge(String),
+
+    /// Error indicating that a size is zero.
+    #[error("Size must be greater than zero")]
+    Zero,
+}
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    /// Error indicating that an output template has a placeholder that doesn't exist.
+    #[error(
+        "Unknown placeholder '{{{0}}}'. Supported placeholders are {{hash}}, {{HASH}}, {{file}}, {{size}}, {{algo}} and {{mtime}}"
+    )]
+    UnknownPlaceholder(String),
+
+    /// Error indicating that a brace of an output template isn't matched.
+    #[error("Unmatched '{0}' in the output template. Braces can be written as '{{{{' and '}}}}'")]
+    UnmatchedBrace(char),
+}
+
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    /// Error indicating that a checkpoint was recorded with other options.
+    #[error(
+        "The checkpoint was recorded for '{found}', but this run is '{expected}'. Remove it or drop --resume to start over"
+    )]
+    DifferentRun { expected: String, found: String },
+
+    /// Error indicating that a checkpoint line couldn't be parsed.
+    #[error("Invalid checkpoint entry on line {0}")]
+    InvalidEntry(usize),
+
+    /// Error indicating that the result of a checked file couldn't be parsed.
+    #[error("Invalid result recorded in the checkpoint for '{0}'")]
+    InvalidResult(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    /// Error indicating that the configuration file has a setting that doesn't exist.
+    #[error("Unknown setting '{0}'. Supported settings are type, length, bsd, jobs and color")]
+    UnknownSetting(String),
+
+    /// Error indicating that a setting of the configuration file or environment has the wrong type.
+    #[error("Invalid value for {name}, expected {expected}")]
+    InvalidValue {
+        name: String,
+        expected: &'static str,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum HashOptionsError {
+    /// Error indicating that a keyed algorithm was used without a key.
+    #[error("{0} requires a key, which can be set with --key, --key-file or --key-env")]
+    MissingKey(String),
+
+    /// Error indicating that a key was provided for an algorithm that doesn't use one.
+    #[error("{0} does not take a key, did you mean to pass --hmac?")]
+    UnexpectedKey(String),
+
+    /// Error indicating that HMAC was requested for an algorithm that isn't a cryptographic digest.
+    #[error("HMAC is not supported with {0}, as it isn't a cryptographic digest")]
+    HmacUnsupported(String),
+
+    /// Error indicating that a salt or personalization was provided for an algorithm that doesn't
+    /// use them.
+    #[error("{0} does not take a salt or personalization")]
+    UnexpectedSaltOrPersonal(String),
+
+    /// Error indicating that a key derivation context was provided for an algorithm that doesn't
+    /// support key derivation.
+    #[error("{0} does not support --derive-key")]
+    UnexpectedContext(String),
+
+    /// Error indicating that a customization string was provided for an algorithm that doesn't use
+    /// one.
+    #[error("{0} does not take a customization string")]
+    UnexpectedCustomization(String),
+
+    /// Error indicating that a seed was provided for an algorithm that doesn't use one.
+    #[error("{0} does not take a seed")]
+    UnexpectedSeed(String),
+
+    /// Error indicating that a bit length was given for an algorithm that doesn't support it.
+    #[error("{name} does not support a length of {bit_length} bits, see `steadyhash list`")]
+    UnsupportedBitLength { name: String, bit_length: usize },
+
+    /// Error indicating that a bit length was given both in the algorithm name and separately.
+    #[error("{name} already sets the bit length, which conflicts with --length {bit_length}")]
+    ConflictingBitLength { name: String, bit_length: usize },
+}
+
+#[derive(Error, Debug)]
+pub enum RegistryError {
+    /// Error indicating that no algorithm is registered under a name.
+    #[error("Unknown algorithm '{0}'")]
+    UnknownAlgorithm(String),
+
+    /// Error indicating that an algorithm was registered under a name that's already taken.
+    #[error("An algorithm called '{0}' is already registered")]
+    DuplicateName(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ExternalHasherError {
+    /// Error indicating that an `--external-hasher` value isn't of the form `NAME=COMMAND`.
+    #[error("Invalid external hasher '{0}'. It must be given as NAME=COMMAND")]
+    InvalidRegistration(String),
+
+    /// Error indicating that an external hasher was registered under the name of a built-in one.
+    #[error("Cannot register the external hasher '{0}', as it's the name of a built-in algorithm")]
+    BuiltinName(String),
+
+    /// Error indicating that the external algorithm was selected without registering its command.
+    #[error("No command is registered for the external hasher")]
+    MissingCommand,
+
+    /// Error indicating that an external hasher was used incrementally, which it doesn't support.
+    #[error("The external hasher '{0}' can only hash whole inputs")]
+    NotIncremental(String),
+
+    /// Error indicating that the command of an external hasher failed.
+    #[error("The external hasher '{name}' failed ({status})")]
+    Failed {
+        name: String,
+        status: std::process::ExitStatus,
+    },
+
+    /// Error indicating that the command of an external hasher didn't print a checksum.
+    #[error("The external hasher '{0}' did not print a checksum")]
+    NoChecksum(String),
+}
+
+#[derive(Error, Debug)]
+pub enum SkeinSumError {
+    /// Error indicating that an output length isn't supported by the chosen Skein state size.
+    #[error(
+        "Invalid output length '{output_bits}' for Skein-{state_bits}. Skein-256 supports 128, 160, 224 and 256 bits, Skein-512 supports 128, 160, 224, 256, 384 and 512 bits, and Skein-1024 supports 384, 512 and 1024 bits"
+    )]
+    InvalidOutputLength {
+        state_bits: usize,
+        output_bits: usize,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum Blake3SumError {
+    /// Error indicating that an invalid BLAKE3 output length has been provided.
+    #[error(
+        "Invalid output length '{0}' bits for BLAKE3. The output length must be a non-zero multiple of 8"
+    )]
+    InvalidOutputLength(usize),
+
+    /// Error indicating that the key doesn't have the size required by BLAKE3.
+    #[error("Invalid BLAKE3 key length of {0} bytes. The key must be exactly 32 bytes long")]
+    InvalidKeyLength(usize),
+}
+
+#[derive(Error, Debug)]
+pub enum Sp800185SumError {
+    /// Error indicating that an invalid output length has been provided for an SP 800-185 function.
+    #[error(
+        "Invalid output length '{0}' bits. The output length of cSHAKE, KMAC and TupleHash must be a non-zero multiple of 8"
+    )]
+    InvalidOutputLength(usize),
+
+    /// Error indicating that an input is too large to be kept in memory by TupleHash.
+    #[error(
+        "TupleHash keeps its whole input in memory, so it only hashes inputs of up to {0} bytes"
+    )]
+    InputTooLarge(u64),
+}
+
 #[derive(Error, Debug)]
 pub enum B2SumError {
     /// Error indicating that an invalid Blake2b checksum type has been provided.
@@ -25,6 +354,20 @@ pub enum B2SumError {
         "Invalid checksum type 'BLAKE2b-{0}'. Supported values are multiples of 8 from 8 up to 512 (inclusive)"
     )]
     InvalidChecksumType(usize),
+
+    /// Error indicating that the BLAKE2b key is too long.
+    #[error("Invalid BLAKE2b key length of {0} bytes. The key can be at most 64 bytes long")]
+    KeyTooLong(usize),
+
+    /// Error indicating that the BLAKE2b salt is too long.
+    #[error("Invalid BLAKE2b salt length of {0} bytes. The salt can be at most 16 bytes long")]
+    SaltTooLong(usize),
+
+    /// Error indicating that the BLAKE2b personalization string is too long.
+    #[error(
+        "Invalid BLAKE2b personalization length of {0} bytes. The personalization can be at most 16 bytes long"
+    )]
+    PersonalTooLong(usize),
 }
 
 #[derive(Debug)]
@@ -0,0 +1,195 @@
+//! C bindings, built into the cdylib with the `steadyhash-ffi` feature. The declarations are in
+//! `include/steadyhash.h`.
+//!
+//! Functions that can fail return a null pointer and store a message, which can be read with
+//! [`steadyhash_last_error`] on the same thread.
+
+use crate::{HashOptions, errors::RegistryError, hashing::DynHasher, registry::Registry};
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    ptr, slice,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Stores the message of a failed call, to be read with [`steadyhash_last_error`].
+fn set_last_error(message: impl ToString) {
+    // messages never contain NUL bytes, except in the name passed by the caller
+    let message = CString::new(message.to_string().replace('\0', "\\0")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// A hasher created by [`steadyhash_hasher_new`].
+pub struct SteadyhashHasher {
+    hasher: Box<dyn DynHasher>,
+}
+
+/// Creates a hasher for the algorithm called `name`, as accepted by `--type` (e.g. `"sha"` or
+/// `"blake3"`). A `bit_length` of 0 selects the default bit length of the algorithm.
+///
+/// `key` is the key of keyed algorithms, and can be null if `key_len` is 0. Returns null if the
+/// algorithm or parameters are invalid.
+///
+/// # Safety
+///
+/// `name` must be a valid NUL-terminated string, and `key` must point to `key_len` readable
+/// bytes unless `key_len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn steadyhash_hasher_new(
+    name: *const c_char,
+    bit_length: usize,
+    key: *const u8,
+    key_len: usize,
+) -> *mut SteadyhashHasher {
+    if name.is_null() {
+        set_last_error("the algorithm name is null");
+        return ptr::null_mut();
+    }
+    // SAFETY: the caller guarantees that `name` is a valid C string
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+
+    let registry = Registry::builtin();
+    let Some(algorithm) = registry.get(&name) else {
+        set_last_error(RegistryError::UnknownAlgorithm(name.into_owned()));
+        return ptr::null_mut();
+    };
+
+    let options = HashOptions {
+        bit_length,
+        key: (key_len > 0).then(|| {
+            // SAFETY: the caller guarantees that `key` points to `key_len` bytes
+            unsafe { slice::from_raw_parts(key, key_len) }.to_vec()
+        }),
+        ..Default::default()
+    };
+
+    match algorithm.hasher(&options) {
+        Ok(hasher) => Box::into_raw(Box::new(SteadyhashHasher { hasher })),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Feeds `len` bytes at `data` to `hasher`.
+///
+/// # Safety
+///
+/// `hasher` must have been returned by [`steadyhash_hasher_new`] and not freed yet, and `data`
+/// must point to `len` readable bytes unless `len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn steadyhash_hasher_update(
+    hasher: *mut SteadyhashHasher,
+    data: *const u8,
+    len: usize,
+) {
+    if hasher.is_null() || len == 0 {
+        return;
+    }
+
+    // SAFETY: the caller guarantees that both pointers are valid
+    let (hasher, data) = unsafe { (&mut *hasher, slice::from_raw_parts(data, len)) };
+    hasher.hasher.update(data);
+}
+
+/// Consumes `hasher` and returns the checksum as a hex string, which must be freed with
+/// [`steadyhash_string_free`].
+///
+/// # Safety
+///
+/// `hasher` must have been returned by [`steadyhash_hasher_new`] and not freed yet. It can't be
+/// used after this call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn steadyhash_hasher_finalize_hex(
+    hasher: *mut SteadyhashHasher,
+) -> *mut c_char {
+    if hasher.is_null() {
+        set_last_error("the hasher is null");
+        return ptr::null_mut();
+    }
+
+    // SAFETY: the caller guarantees that `hasher` came from `Box::into_raw`
+    let hasher = unsafe { Box::from_raw(hasher) };
+    // hex digests never contain NUL bytes
+    CString::new(hasher.hasher.finalize())
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Frees a hasher without computing its checksum. Does nothing if `hasher` is null.
+///
+/// # Safety
+///
+/// `hasher` must have been returned by [`steadyhash_hasher_new`] and not freed or finalized yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn steadyhash_hasher_free(hasher: *mut SteadyhashHasher) {
+    if !hasher.is_null() {
+        // SAFETY: the caller guarantees that `hasher` came from `Box::into_raw`
+        drop(unsafe { Box::from_raw(hasher) });
+    }
+}
+
+/// Frees a string returned by [`steadyhash_hasher_finalize_hex`]. Does nothing if `s` is null.
+///
+/// # Safety
+///
+/// `s` must have been returned by [`steadyhash_hasher_finalize_hex`] and not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn steadyhash_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the caller guarantees that `s` came from `CString::into_raw`
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Returns the message of the last failed call on this thread, or null if there wasn't one. The
+/// string is owned by the library and stays valid until the next failed call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn steadyhash_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_by_name() {
+        unsafe {
+            let hasher = steadyhash_hasher_new(c"sha".as_ptr(), 256, ptr::null(), 0);
+            assert!(!hasher.is_null());
+            steadyhash_hasher_update(hasher, b"ab".as_ptr(), 2);
+            steadyhash_hasher_update(hasher, b"c".as_ptr(), 1);
+
+            let hex = steadyhash_hasher_finalize_hex(hasher);
+            assert_eq!(
+                CStr::from_ptr(hex).to_str().unwrap(),
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            );
+            steadyhash_string_free(hex);
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        unsafe {
+            // keyed algorithm without a key
+            let hasher = steadyhash_hasher_new(c"siphash".as_ptr(), 0, ptr::null(), 0);
+            assert!(hasher.is_null());
+            assert!(!steadyhash_last_error().is_null());
+
+            let hasher = steadyhash_hasher_new(c"nope".as_ptr(), 0, ptr::null(), 0);
+            assert!(hasher.is_null());
+            let message = CStr::from_ptr(steadyhash_last_error());
+            assert!(message.to_str().unwrap().contains("nope"));
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Collecting the files to hash, from the command line, the lists given to `--files-from` and
+//! the directories walked with `--recursive`.
+
+use anyhow::{Context, Error};
+use globset::{GlobBuilder, GlobMatcher};
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::{MAIN_SEPARATOR, Path, PathBuf},
+    str::FromStr,
+};
+
+/// A glob pattern of `--include` or `--exclude`, like `*.o`, `target/` or `docs/*.html`.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    matcher: GlobMatcher,
+
+    /// Whether the pattern contains a `/`, so that it's matched against the path relative to the
+    /// walked directory rather than against the file name
+    anchored: bool,
+
+    /// Whether the pattern ends with a `/`, so that it only matches directories
+    dir_only: bool,
+}
+
+impl Pattern {
+    /// Whether the pattern matches the file or directory at `relative`, relative to the walked
+    /// directory.
+    fn matches(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            self.matcher.is_match(relative)
+        } else {
+            relative
+                .file_name()
+                .is_some_and(|name| self.matcher.is_match(name))
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = globset::Error;
+
+    fn from_str(pattern: &str) -> Result<Pattern, globset::Error> {
+        let (pattern, dir_only) = match pattern.strip_suffix('/') {
+            Some(pattern) => (pattern, true),
+            None => (pattern, false),
+        };
+        // like in .gitignore files, a leading `/` anchors a pattern without other slashes
+        let anchored = pattern.contains('/');
+        let glob = GlobBuilder::new(pattern.trim_start_matches('/'))
+            .literal_separator(true)
+            .build()?;
+
+        Ok(Pattern {
+            matcher: glob.compile_matcher(),
+            anchored,
+            dir_only,
+        })
+    }
+}
+
+/// How directories are walked with `--recursive`.
+#[derive(Default)]
+pub struct WalkOptions {
+    /// Patterns of the files to hash, or none to hash every file
+    pub include: Vec<Pattern>,
+
+    /// Patterns of the files and directories to skip, even if they're included
+    pub exclude: Vec<Pattern>,
+
+    /// How deep to walk, like `find -maxdepth`: at 1, only the files directly in the directories
+    /// are hashed
+    pub max_depth: Option<usize>,
+
+    /// Whether files and directories whose name starts with a `.` are skipped
+    pub skip_hidden: bool,
+
+    /// Whether symlinks found in the directories are followed, unless they lead back to a
+    /// directory being walked
+    pub follow_symlinks: bool,
+
+    /// Whether symlinks found in the directories are listed themselves, when they aren't followed
+    pub keep_symlinks: bool,
+
+    /// Canonical paths of the files which are never listed, like the manifests written by the run
+    pub skip: Vec<PathBuf>,
+}
+
+impl WalkOptions {
+    /// Whether the file or directory at `relative`, relative to the walked directory, is skipped.
+    fn skips(&self, relative: &Path, is_dir: bool) -> bool {
+        let matches = |patterns: &[Pattern]| {
+            patterns
+                .iter()
+                .any(|pattern| pattern.matches(relative, is_dir))
+        };
+        matches(&self.exclude) || (!is_dir && !self.include.is_empty() && !matches(&self.include))
+    }
+
+    /// Whether the file at `path` is one of the files to skip. Only the files with the same name as
+    /// one of them are canonicalized.
+    fn is_skipped(&self, path: &Path) -> bool {
+        self.skip
+            .iter()
+            .any(|skip| skip.file_name() == path.file_name())
+            && fs::canonicalize(path).is_ok_and(|path| self.skip.contains(&path))
+    }
+}
+
+/// Reads the paths listed in the file at `path`, or in stdin for `-`, one per line, or
+/// NUL-terminated with `nul`, as written by `find -print0`. Empty entries are skipped.
+pub fn read_list(path: &Path, nul: bool) -> Result<Vec<PathBuf>, Error> {
+    let reader: Box<dyn BufRead> = if path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open file list {}", path.display()))?;
+        Box::new(BufReader::new(file))
+    };
+
+    parse_list(reader, nul).with_context(|| format!("failed to read file list {}", path.display()))
+}
+
+/// Reads the patterns of `--exclude-from`, one per line. Blank lines and `#` comments are skipped.
+pub fn read_patterns(path: &Path) -> Result<Vec<Pattern>, Error> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read patterns {}", path.display()))?;
+
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.parse()
+                .with_context(|| format!("invalid pattern '{line}' in {}", path.display()))
+        })
+        .collect()
+}
+
+/// Replaces the directories of `paths` with the regular files they contain, recursively. Unless
+/// `options` follow them, symlinks found in the directories are skipped, but the paths themselves
+/// may be symlinks.
+pub fn expand(paths: Vec<PathBuf>, options: &WalkOptions) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        if path.is_dir() {
+            let mut walk = Walk {
+                root: &path,
+                options,
+                ancestors: Vec::new(),
+                files: &mut files,
+            };
+            if options.follow_symlinks {
+                walk.ancestors.push(fs::canonicalize(&path)?);
+            }
+            walk.walk(&path, 1)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// The walk of a directory given to `expand`.
+struct Walk<'a> {
+    root: &'a Path,
+    options: &'a WalkOptions,
+
+    /// Canonical paths of the directories being walked when following symlinks, which a symlink
+    /// leading to one of them would loop back to
+    ancestors: Vec<PathBuf>,
+
+    files: &'a mut Vec<PathBuf>,
+}
+
+impl Walk<'_> {
+    /// Appends the regular files under `dir` to the files, in the order the directories list
+    /// them. The entries of `dir` are `depth` levels below the root.
+    fn walk(&mut self, dir: &Path, depth: usize) -> Result<(), Error> {
+        if self
+            .options
+            .max_depth
+            .is_some_and(|max_depth| depth > max_depth)
+        {
+            return Ok(());
+        }
+
+        let entries = fs::read_dir(dir)
+            .with_context(|| format!("failed to read directory {}", dir.display()))?;
+        for entry in entries {
+            let entry =
+                entry.with_context(|| format!("failed to read directory {}", dir.display()))?;
+            if self.options.skip_hidden && entry.file_name().as_encoded_bytes().starts_with(b".") {
+                continue;
+            }
+
+            let path = entry.path();
+            let mut file_type = entry.file_type()?;
+            if file_type.is_symlink() && self.options.follow_symlinks {
+                // broken symlinks have nothing to hash
+                match fs::metadata(&path) {
+                    Ok(metadata) => file_type = metadata.file_type(),
+                    Err(_) => continue,
+                }
+            }
+            let relative = path.strip_prefix(self.root).unwrap_or(&path);
+            if self.options.skips(relative, file_type.is_dir()) {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                if !self.options.follow_symlinks {
+                    self.walk(&path, depth + 1)?;
+                    continue;
+                }
+                let canonical = fs::canonicalize(&path)?;
+                if self.ancestors.contains(&canonical) {
+                    eprintln!("warning: skipping {}, a symlink loop", path.display());
+                    continue;
+                }
+                self.ancestors.push(canonical);
+                self.walk(&path, depth + 1)?;
+                self.ancestors.pop();
+            } else if (file_type.is_file()
+                || (file_type.is_symlink() && self.options.keep_symlinks))
+                && !self.options.is_skipped(&path)
+            {
+                self.files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sorts `files` by the bytes of their paths, like `LC_ALL=C sort`, so that the manifests written
+/// from them are the same across runs and machines. On platforms with another separator, like
+/// Windows, the paths are rewritten with `/` first.
+pub fn sort(files: &mut [PathBuf]) {
+    if MAIN_SEPARATOR != '/' {
+        for file in files.iter_mut() {
+            *file = file.to_string_lossy().replace(MAIN_SEPARATOR, "/").into();
+        }
+    }
+    files.sort_by(|a, b| {
+        a.as_os_str()
+            .as_encoded_bytes()
+            .cmp(b.as_os_str().as_encoded_bytes())
+    });
+}
+
+fn parse_list(reader: impl BufRead, nul: bool) -> Result<Vec<PathBuf>, Error> {
+    let delimiter = if nul { b'\0' } else { b'\n' };
+    let mut files = Vec::new();
+
+    for entry in reader.split(delimiter) {
+        let entry =
+            String::from_utf8(entry?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if !entry.is_empty() {
+            files.push(entry.into());
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    #[test]
+    fn test_parse_list() {
+        let files = parse_list(&b"a\n\nb c\n"[..], false).unwrap();
+        assert_eq!(files, [PathBuf::from("a"), PathBuf::from("b c")]);
+
+        // NUL-terminated paths may contain newlines
+        let files = parse_list(&b"a\nb\0c\0"[..], true).unwrap();
+        assert_eq!(files, [PathBuf::from("a\nb"), PathBuf::from("c")]);
+        assert!(parse_list(&b"\xff\n"[..], false).is_err());
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut files = ["b", "a/b", "a-b", "B", "a"].map(PathBuf::from);
+        sort(&mut files);
+        assert_eq!(files, ["B", "a", "a-b", "a/b", "b"].map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_expand() {
+        let dir = env::temp_dir().join(format!("steadyhash-files-{}", process::id()));
+        fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        for file in ["a", "sub/b", "sub/deeper/c"] {
+            fs::write(dir.join(file), file).unwrap();
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&dir, dir.join("sub/loop")).unwrap();
+
+        let walked = |options: &WalkOptions| {
+            let mut files = expand(vec![dir.clone()], options).unwrap();
+            files.sort();
+            files
+        };
+        let files = expand(vec![dir.clone(), "missing".into()], &WalkOptions::default()).unwrap();
+        assert_eq!(files.len(), 4);
+        assert_eq!(files[3], PathBuf::from("missing"));
+        let all = ["a", "sub/b", "sub/deeper/c"].map(|file| dir.join(file));
+        assert_eq!(walked(&WalkOptions::default()), all);
+
+        let patterns = |patterns: &[&str]| patterns.iter().map(|p| p.parse().unwrap()).collect();
+        let options = WalkOptions {
+            include: patterns(&["[bc]"]),
+            exclude: patterns(&["deeper/"]),
+            ..Default::default()
+        };
+        assert_eq!(walked(&options), [dir.join("sub/b")]);
+        let options = WalkOptions {
+            exclude: patterns(&["/a", "sub/*/c"]),
+            ..Default::default()
+        };
+        assert_eq!(walked(&options), [dir.join("sub/b")]);
+
+        let options = WalkOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(walked(&options), all[..2]);
+        fs::write(dir.join("sub/.hidden"), "").unwrap();
+        let options = WalkOptions {
+            skip_hidden: true,
+            ..Default::default()
+        };
+        assert_eq!(walked(&options), all);
+
+        // the loop back to the walked directory is skipped
+        #[cfg(unix)]
+        {
+            let options = WalkOptions {
+                follow_symlinks: true,
+                ..Default::default()
+            };
+            assert_eq!(walked(&options).len(), 4);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
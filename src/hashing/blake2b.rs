@@ -1,20 +1,25 @@
 use crate::errors::B2SumError;
 use crate::hashing::Hasher;
 
-use blake2::Blake2bVar;
-use digest::{Update, VariableOutput};
+use blake2b_simd::{KEYBYTES, PERSONALBYTES, Params, SALTBYTES, State};
 use std::fmt::Write;
 
-/// Blake2b hasher that supports runtime-specified bit lengths (multiples of 8, up to 512).
-pub struct Blake2b<'a> {
-    /// Bit length of the checksum (e.g. 256, 512, 384, 224, 128, ... but must be multiple of 8)
-    checksum_type: usize,
-
-    /// Data to process
-    data: &'a [u8],
+/// Blake2b hasher that supports runtime-specified bit lengths (multiples of 8, up to 512), as well
+/// as BLAKE2's native keyed mode, salt and personalization.
+///
+/// The parameters must be set before any data is fed to the hasher, as changing them resets the
+/// running state.
+pub struct Blake2b {
+    /// Bit length of the checksum (e.g. 256, 512, 384, 224, 128, ... but must be multiple of 8),
+    /// along with the optional key (up to 64 bytes), salt (up to 16 bytes) and personalization
+    /// string (up to 16 bytes)
+    params: Params,
+
+    /// Running state, created from `params`
+    state: State,
 }
 
-impl Hasher for Blake2b<'_> {
+impl Hasher for Blake2b {
     // all valid multiples of 8 from 8..=512 (8 * 1 .. 8 * 64)
     const VALID_VALUES: &'static [usize] = &[
         8, 16, 24, 32, 40, 48, 56, 64, 72, 80, 88, 96, 104, 112, 120, 128, 136, 144, 152, 160, 168,
@@ -23,27 +28,16 @@ impl Hasher for Blake2b<'_> {
         464, 472, 480, 488, 496, 504, 512,
     ];
 
-    fn get_checksum(&self) -> String {
-        // validate, even though this should already be validated in new(), but double-check here
-        // just in case
-        let bits = self.checksum_type;
-        if bits == 0 || !bits.is_multiple_of(8) || bits > 512 {
-            unreachable!();
-        }
-
-        let out_bytes = bits / 8;
-
-        let mut hasher = Blake2bVar::new(out_bytes).unwrap();
-
-        hasher.update(self.data);
+    fn update(&mut self, data: &[u8]) {
+        self.state.update(data);
+    }
 
-        // finalize into buffer of the requested size
-        let mut buf = vec![0u8; out_bytes];
-        hasher.finalize_variable(&mut buf).unwrap();
+    fn finalize(self) -> String {
+        let hash = self.state.finalize();
 
         // hex-encode without extra dependency
-        let mut s = String::with_capacity(out_bytes * 2);
-        for b in buf {
+        let mut s = String::with_capacity(hash.as_bytes().len() * 2);
+        for b in hash.as_bytes() {
             write!(&mut s, "{b:02x}").expect("writing to string cannot fail");
         }
 
@@ -51,17 +45,53 @@ impl Hasher for Blake2b<'_> {
     }
 }
 
-impl<'a> Blake2b<'a> {
-    pub fn new(checksum_type: usize, data: &'a [u8]) -> Result<Self, B2SumError> {
+impl Blake2b {
+    pub fn new(checksum_type: usize) -> Result<Self, B2SumError> {
         if !Self::VALID_VALUES.contains(&(checksum_type)) {
             return Err(B2SumError::InvalidChecksumType(checksum_type));
         }
 
+        let mut params = Params::new();
+        params.hash_length(checksum_type / 8);
+
         Ok(Blake2b {
-            checksum_type,
-            data,
+            state: params.to_state(),
+            params,
         })
     }
+
+    /// Sets the key, making this a keyed hash (BLAKE2b's built-in MAC).
+    pub fn with_key(mut self, key: &[u8]) -> Result<Self, B2SumError> {
+        if key.len() > KEYBYTES {
+            return Err(B2SumError::KeyTooLong(key.len()));
+        }
+
+        self.params.key(key);
+        self.state = self.params.to_state();
+        Ok(self)
+    }
+
+    /// Sets the salt. Shorter salts are padded with zeros.
+    pub fn with_salt(mut self, salt: &[u8]) -> Result<Self, B2SumError> {
+        if salt.len() > SALTBYTES {
+            return Err(B2SumError::SaltTooLong(salt.len()));
+        }
+
+        self.params.salt(salt);
+        self.state = self.params.to_state();
+        Ok(self)
+    }
+
+    /// Sets the personalization string. Shorter strings are padded with zeros.
+    pub fn with_personal(mut self, personal: &[u8]) -> Result<Self, B2SumError> {
+        if personal.len() > PERSONALBYTES {
+            return Err(B2SumError::PersonalTooLong(personal.len()));
+        }
+
+        self.params.personal(personal);
+        self.state = self.params.to_state();
+        Ok(self)
+    }
 }
 
 mod tests {
@@ -72,21 +102,42 @@ mod tests {
     fn test_hi() {
         let text = b"hi";
 
-        let checksum = Blake2b::new(512, text).unwrap();
+        let checksum = Blake2b::new(512).unwrap();
         assert_eq!(
-            checksum.get_checksum(),
+            checksum.checksum(text),
             "bfbcbe7ade93034ee0a41a2ea7b5fd81d89bdb1d75d1af230ea37d7abe71078f1df6db4d251cbc6b58e8963db2546f0f539c80b0f08c0fdd8c0a71075c97b3e7"
         );
     }
 
+    #[test]
+    fn test_keyed() {
+        // hashlib.blake2b(b"hi", digest_size=32, key=b"key", salt=b"salt", person=b"me")
+        let checksum = Blake2b::new(256)
+            .and_then(|b| b.with_key(b"key"))
+            .and_then(|b| b.with_salt(b"salt"))
+            .and_then(|b| b.with_personal(b"me"))
+            .unwrap();
+        assert_eq!(
+            checksum.checksum(b"hi"),
+            "ac027c37b6870fb4f23bf6556c2a37c1a91a09488aab395393a901145927ffe3"
+        );
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert!(Blake2b::new(512).unwrap().with_key(&[0; 65]).is_err());
+        assert!(Blake2b::new(512).unwrap().with_salt(&[0; 17]).is_err());
+        assert!(Blake2b::new(512).unwrap().with_personal(&[0; 17]).is_err());
+    }
+
     #[test]
     fn test_invalid_bit_length() {
         assert!(
-            Blake2b::new(4, b"").is_err(),
+            Blake2b::new(4).is_err(),
             "bit length must be bigger or equal than 8"
         );
         assert!(
-            Blake2b::new(13, b"").is_err(),
+            Blake2b::new(13).is_err(),
             "bit length must be a multiple of 8"
         );
     }
@@ -95,7 +146,7 @@ mod tests {
     fn test_valid_bit_lengths() {
         let mut i = 8;
         while i <= 512 {
-            assert!(Blake2b::new(i, b"").is_ok());
+            assert!(Blake2b::new(i).is_ok());
             i += 8;
         }
     }
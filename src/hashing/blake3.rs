@@ -0,0 +1,129 @@
+use crate::errors::Blake3SumError;
+use crate::hashing::Hasher;
+
+/// Smallest update that's worth splitting across threads, as recommended by the `blake3` crate.
+const MIN_PARALLEL_LEN: usize = 128 * 1024;
+
+/// BLAKE3 hasher producing an output of arbitrary length (any non-zero multiple of 8 bits).
+///
+/// Besides regular hashing, it supports the keyed (a MAC, with a 32-byte key) and key derivation
+/// (with a hardcoded, globally unique context string) modes exposed by `b3sum`. The mode must be
+/// picked before any data is fed to the hasher.
+///
+/// Large updates can be hashed on several threads, using the global rayon thread pool.
+pub struct Blake3Sum {
+    /// Bit length of the output
+    output_bits: usize,
+
+    /// Whether to hash large updates on several threads
+    multithreaded: bool,
+
+    /// Running state, initialized for the hashing mode
+    hasher: blake3::Hasher,
+}
+
+impl Hasher for Blake3Sum {
+    // BLAKE3 is an XOF, see Blake3Sum::new for validation
+    const VALID_VALUES: &'static [usize] = &[];
+
+    fn update(&mut self, data: &[u8]) {
+        if self.multithreaded && data.len() >= MIN_PARALLEL_LEN {
+            self.hasher.update_rayon(data);
+        } else {
+            self.hasher.update(data);
+        }
+    }
+
+    fn finalize(self) -> String {
+        let mut buf = vec![0u8; self.output_bits / 8];
+        self.hasher.finalize_xof().fill(&mut buf);
+
+        hex::encode(buf)
+    }
+}
+
+impl Blake3Sum {
+    pub fn new(output_bits: usize) -> Result<Blake3Sum, Blake3SumError> {
+        if output_bits == 0 || !output_bits.is_multiple_of(8) {
+            return Err(Blake3SumError::InvalidOutputLength(output_bits));
+        }
+
+        Ok(Blake3Sum {
+            output_bits,
+            multithreaded: false,
+            hasher: blake3::Hasher::new(),
+        })
+    }
+
+    /// Switches to keyed hashing mode.
+    pub fn with_key(mut self, key: &[u8]) -> Result<Self, Blake3SumError> {
+        let key = key
+            .try_into()
+            .map_err(|_| Blake3SumError::InvalidKeyLength(key.len()))?;
+
+        self.hasher = blake3::Hasher::new_keyed(key);
+        Ok(self)
+    }
+
+    /// Switches to key derivation mode, with the given context string.
+    pub fn with_derive_key(mut self, context: &str) -> Self {
+        self.hasher = blake3::Hasher::new_derive_key(context);
+        self
+    }
+
+    /// Enables hashing large updates on several threads.
+    pub fn with_multithreading(mut self, multithreaded: bool) -> Self {
+        self.multithreaded = multithreaded;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // from the official test_vectors.json
+    const KEY: &[u8] = b"whats the Elvish word for friend";
+    const CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            Blake3Sum::new(256).unwrap().checksum(b""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[test]
+    fn test_keyed() {
+        let checksum = Blake3Sum::new(256).unwrap().with_key(KEY).unwrap();
+        assert_eq!(
+            checksum.checksum(b""),
+            "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26"
+        );
+
+        assert!(Blake3Sum::new(256).unwrap().with_key(&KEY[..16]).is_err());
+    }
+
+    #[test]
+    fn test_multithreaded_matches() {
+        let data = vec![0xab; MIN_PARALLEL_LEN * 4 + 1];
+
+        assert_eq!(
+            Blake3Sum::new(256)
+                .unwrap()
+                .with_multithreading(true)
+                .checksum(&data),
+            Blake3Sum::new(256).unwrap().checksum(&data)
+        );
+    }
+
+    #[test]
+    fn test_derive_key() {
+        let checksum = Blake3Sum::new(256).unwrap().with_derive_key(CONTEXT);
+        assert_eq!(
+            checksum.checksum(b""),
+            "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::hashing::Hasher;
+use crc::{CRC_32_CKSUM, CRC_64_ECMA_182, CRC_64_XZ, Crc, Digest};
+
+static CKSUM: Crc<u32> = Crc::<u32>::new(&CRC_32_CKSUM);
+static CRC64_XZ: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+static CRC64_ECMA: Crc<u64> = Crc::<u64>::new(&CRC_64_ECMA_182);
+
+/// CRC-32 using the zlib/ISO-HDLC polynomial, as found in gzip, zip and PNG.
+#[derive(Default)]
+pub struct Crc32Sum {
+    /// Running CRC
+    hasher: crc32fast::Hasher,
+}
+
+impl Hasher for Crc32Sum {
+    const VALID_VALUES: &'static [usize] = &[32];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        format!("{:08x}", self.hasher.finalize())
+    }
+}
+
+impl Crc32Sum {
+    pub fn new() -> Crc32Sum {
+        Crc32Sum::default()
+    }
+}
+
+/// CRC-32C using the Castagnoli polynomial (hardware-accelerated where the CPU supports it).
+#[derive(Default)]
+pub struct Crc32cSum {
+    /// Running CRC
+    crc: u32,
+}
+
+impl Hasher for Crc32cSum {
+    const VALID_VALUES: &'static [usize] = &[32];
+
+    fn update(&mut self, data: &[u8]) {
+        self.crc = crc32c::crc32c_append(self.crc, data);
+    }
+
+    fn finalize(self) -> String {
+        format!("{:08x}", self.crc)
+    }
+}
+
+impl Crc32cSum {
+    pub fn new() -> Crc32cSum {
+        Crc32cSum::default()
+    }
+}
+
+/// POSIX `cksum` checksum: a CRC-32 (unreflected, polynomial 0x04C11DB7) over the data followed by
+/// its length.
+///
+/// The checksum is rendered the way `cksum` prints it, as the decimal CRC followed by the decimal
+/// byte count (e.g. `4294967295 0`).
+pub struct CksumSum {
+    /// Running CRC
+    digest: Digest<'static, u32>,
+
+    /// Number of bytes hashed so far
+    len: u64,
+}
+
+impl Hasher for CksumSum {
+    const VALID_VALUES: &'static [usize] = &[32];
+
+    fn update(&mut self, data: &[u8]) {
+        self.digest.update(data);
+        self.len += data.len() as u64;
+    }
+
+    fn finalize(mut self) -> String {
+        // the length is appended least significant byte first, using as few bytes as possible
+        let mut len = self.len;
+        while len > 0 {
+            self.digest.update(&[(len & 0xff) as u8]);
+            len >>= 8;
+        }
+
+        format!("{} {}", self.digest.finalize(), self.len)
+    }
+}
+
+impl CksumSum {
+    pub fn new() -> CksumSum {
+        CksumSum {
+            digest: CKSUM.digest(),
+            len: 0,
+        }
+    }
+}
+
+impl Default for CksumSum {
+    fn default() -> Self {
+        CksumSum::new()
+    }
+}
+
+/// CRC-64 variants.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Crc64Variant {
+    /// CRC-64/XZ (also known as CRC-64/GO-ECMA), as reported by `xz -lvv`
+    Xz,
+    /// CRC-64/ECMA-182, the unreflected variant
+    Ecma,
+}
+
+pub struct Crc64Sum {
+    /// Running CRC, using the polynomial and parameter set of the variant
+    digest: Digest<'static, u64>,
+}
+
+impl Hasher for Crc64Sum {
+    const VALID_VALUES: &'static [usize] = &[64];
+
+    fn update(&mut self, data: &[u8]) {
+        self.digest.update(data);
+    }
+
+    fn finalize(self) -> String {
+        format!("{:016x}", self.digest.finalize())
+    }
+}
+
+impl Crc64Sum {
+    pub fn new(variant: Crc64Variant) -> Crc64Sum {
+        let digest = match variant {
+            Crc64Variant::Xz => CRC64_XZ.digest(),
+            Crc64Variant::Ecma => CRC64_ECMA.digest(),
+        };
+
+        Crc64Sum { digest }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the standard "check" input used by the CRC catalogue
+    const CHECK_INPUT: &[u8] = b"123456789";
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(Crc32Sum::new().checksum(CHECK_INPUT), "cbf43926");
+        assert_eq!(Crc32Sum::new().checksum(b""), "00000000");
+    }
+
+    #[test]
+    fn test_crc32c_split() {
+        let mut checksum = Crc32cSum::new();
+        checksum.update(&CHECK_INPUT[..4]);
+        checksum.update(&CHECK_INPUT[4..]);
+        assert_eq!(checksum.finalize(), "e3069283");
+    }
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(Crc32cSum::new().checksum(CHECK_INPUT), "e3069283");
+    }
+
+    #[test]
+    fn test_cksum() {
+        // printf 123456789 | cksum
+        assert_eq!(CksumSum::new().checksum(CHECK_INPUT), "930766865 9");
+        assert_eq!(CksumSum::new().checksum(b""), "4294967295 0");
+    }
+
+    #[test]
+    fn test_crc64() {
+        assert_eq!(
+            Crc64Sum::new(Crc64Variant::Xz).checksum(CHECK_INPUT),
+            "995dc9bbdf1939fa"
+        );
+        assert_eq!(
+            Crc64Sum::new(Crc64Variant::Ecma).checksum(CHECK_INPUT),
+            "6c40df5f0b497347"
+        );
+    }
+}
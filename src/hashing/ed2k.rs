@@ -0,0 +1,114 @@
+use crate::hashing::Hasher;
+use digest::Digest;
+use md4::Md4;
+
+/// Size of an eDonkey2000 chunk, in bytes.
+pub const CHUNK_SIZE: usize = 9_728_000;
+
+/// eDonkey2000 hasher (the hash used in `ed2k://` links).
+///
+/// Data smaller than one chunk is hashed with plain MD4. Otherwise, each chunk is hashed with MD4
+/// and the result is the MD4 of the concatenated chunk hashes. For data whose length is an exact
+/// multiple of the chunk size, the hash of a trailing empty chunk is included, as eMule and rhash
+/// do.
+#[derive(Default)]
+pub struct Ed2kSum {
+    /// Hash of the chunk being filled
+    chunk: Md4,
+
+    /// Number of bytes in the chunk being filled
+    chunk_len: usize,
+
+    /// Hash of the hashes of the completed chunks
+    root: Md4,
+
+    /// Whether at least one chunk has been completed
+    chunked: bool,
+}
+
+impl Hasher for Ed2kSum {
+    const VALID_VALUES: &'static [usize] = &[128];
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let len = data.len().min(CHUNK_SIZE - self.chunk_len);
+            self.chunk.update(&data[..len]);
+            self.chunk_len += len;
+            data = &data[len..];
+
+            if self.chunk_len == CHUNK_SIZE {
+                let chunk = std::mem::take(&mut self.chunk);
+                self.root.update(chunk.finalize());
+                self.chunk_len = 0;
+                self.chunked = true;
+            }
+        }
+    }
+
+    fn finalize(mut self) -> String {
+        if !self.chunked {
+            return hex::encode(self.chunk.finalize());
+        }
+
+        // the last chunk may be empty, which gives the trailing empty chunk hash
+        self.root.update(self.chunk.finalize());
+        hex::encode(self.root.finalize())
+    }
+}
+
+impl Ed2kSum {
+    pub fn new() -> Ed2kSum {
+        Ed2kSum::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_chunk_is_md4() {
+        assert_eq!(
+            Ed2kSum::new().checksum(b""),
+            "31d6cfe0d16ae931b73c59d7e0c089c0"
+        );
+        assert_eq!(
+            Ed2kSum::new().checksum(b"abc"),
+            "a448017aaf21d8525fc10ae87aa6729d"
+        );
+    }
+
+    #[test]
+    fn test_chunked() {
+        let data = vec![0u8; CHUNK_SIZE + 1];
+
+        let mut root = Md4::new();
+        root.update(Md4::digest(&data[..CHUNK_SIZE]));
+        root.update(Md4::digest([0u8]));
+
+        assert_eq!(Ed2kSum::new().checksum(&data), hex::encode(root.finalize()));
+    }
+
+    #[test]
+    fn test_split_across_chunks() {
+        let data = vec![1u8; CHUNK_SIZE * 2 + 5];
+
+        let mut checksum = Ed2kSum::new();
+        for piece in data.chunks(CHUNK_SIZE / 3 + 7) {
+            checksum.update(piece);
+        }
+
+        assert_eq!(checksum.finalize(), Ed2kSum::new().checksum(&data));
+    }
+
+    #[test]
+    fn test_exact_chunk_multiple() {
+        let data = vec![0u8; CHUNK_SIZE];
+
+        let mut root = Md4::new();
+        root.update(Md4::digest(&data));
+        root.update(Md4::digest([]));
+
+        assert_eq!(Ed2kSum::new().checksum(&data), hex::encode(root.finalize()));
+    }
+}
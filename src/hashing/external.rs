@@ -0,0 +1,126 @@
+//! Checksums computed by an external command, for algorithms that aren't built in.
+
+use crate::{
+    errors::ExternalHasherError,
+    hashing::{Input, feed},
+};
+use anyhow::{Context, Error};
+use std::{
+    io::{ErrorKind, Read, Write},
+    process::{Command, Stdio},
+    thread,
+};
+
+/// An algorithm implemented by a command, which reads the data on its stdin and prints the
+/// checksum as the first word of its stdout, like `sha256sum` or `b3sum` do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalHasher {
+    /// Name the algorithm is selected by, and shown as in BSD-style output
+    pub name: String,
+
+    /// Command line, run through the shell
+    pub command: String,
+}
+
+impl ExternalHasher {
+    /// Runs the command on `input` and returns the checksum it printed.
+    pub fn run(&self, input: Input) -> Result<String, Error> {
+        let mut child = shell(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run the {} hasher", self.name))?;
+
+        // read the output while writing, so that neither side blocks on a full pipe
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let output = thread::spawn(move || {
+            let mut output = String::new();
+            stdout.read_to_string(&mut output).map(|_| output)
+        });
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let mut write_error = None;
+        let fed = feed(input, |data| {
+            if write_error.is_none()
+                && let Err(e) = stdin.write_all(data)
+            {
+                write_error = Some(e);
+            }
+        });
+        // closing stdin lets the command know the input is over
+        drop(stdin);
+
+        let output = output.join().expect("reading the output doesn't panic");
+        let status = child.wait()?;
+        fed?;
+        if !status.success() {
+            return Err(ExternalHasherError::Failed {
+                name: self.name.clone(),
+                status,
+            }
+            .into());
+        }
+        match write_error {
+            // the command may have stopped reading because it didn't need the rest
+            Some(e) if e.kind() != ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => {}
+        }
+
+        output?
+            .split_whitespace()
+            .next()
+            .map(str::to_owned)
+            .ok_or_else(|| ExternalHasherError::NoChecksum(self.name.clone()).into())
+    }
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// Parses a `NAME=COMMAND` registration, as given to `--external-hasher`.
+impl std::str::FromStr for ExternalHasher {
+    type Err = ExternalHasherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((name, command)) if !name.is_empty() && !command.trim().is_empty() => {
+                Ok(ExternalHasher {
+                    name: name.to_owned(),
+                    command: command.to_owned(),
+                })
+            }
+            _ => Err(ExternalHasherError::InvalidRegistration(s.to_owned())),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let hasher: ExternalHasher = "count=wc -c".parse().unwrap();
+        let mut data = &[0u8; 100_000][..];
+        let input = Input::Reader {
+            reader: &mut data,
+            buffer_size: 4096,
+        };
+        assert_eq!(hasher.run(input).unwrap(), "100000");
+
+        let failing: ExternalHasher = "fail=false".parse().unwrap();
+        assert!(failing.run(Input::Bytes(b"")).is_err());
+        assert!("=cmd".parse::<ExternalHasher>().is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::errors::FnvSumError;
+use crate::hashing::Hasher;
+
+const FNV32_OFFSET: u32 = 0x811c_9dc5;
+const FNV32_PRIME: u32 = 0x0100_0193;
+const FNV64_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV64_PRIME: u64 = 0x0000_0100_0000_01b3;
+const FNV128_OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const FNV128_PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+/// Running FNV-1a hash, for each supported bit length.
+enum FnvState {
+    Fnv32(u32),
+    Fnv64(u64),
+    Fnv128(u128),
+}
+
+/// FNV-1a hasher. This is a fast, non-cryptographic hash: do not use it to detect tampering.
+pub struct FnvSum {
+    /// Running hash, picked from the bit length of the checksum (32, 64 or 128)
+    state: FnvState,
+}
+
+impl Hasher for FnvSum {
+    const VALID_VALUES: &'static [usize] = &[32, 64, 128];
+
+    fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            FnvState::Fnv32(hash) => {
+                *hash = data.iter().fold(*hash, |hash, &b| {
+                    (hash ^ u32::from(b)).wrapping_mul(FNV32_PRIME)
+                });
+            }
+            FnvState::Fnv64(hash) => {
+                *hash = data.iter().fold(*hash, |hash, &b| {
+                    (hash ^ u64::from(b)).wrapping_mul(FNV64_PRIME)
+                });
+            }
+            FnvState::Fnv128(hash) => {
+                *hash = data.iter().fold(*hash, |hash, &b| {
+                    (hash ^ u128::from(b)).wrapping_mul(FNV128_PRIME)
+                });
+            }
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self.state {
+            FnvState::Fnv32(hash) => format!("{hash:08x}"),
+            FnvState::Fnv64(hash) => format!("{hash:016x}"),
+            FnvState::Fnv128(hash) => format!("{hash:032x}"),
+        }
+    }
+}
+
+impl FnvSum {
+    pub fn new(checksum_bits: usize) -> Result<FnvSum, FnvSumError> {
+        let state = match checksum_bits {
+            32 => FnvState::Fnv32(FNV32_OFFSET),
+            64 => FnvState::Fnv64(FNV64_OFFSET),
+            128 => FnvState::Fnv128(FNV128_OFFSET),
+            _ => return Err(FnvSumError::InvalidChecksumType(checksum_bits)),
+        };
+
+        Ok(FnvSum { state })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_is_offset_basis() {
+        assert_eq!(FnvSum::new(32).unwrap().checksum(b""), "811c9dc5");
+        assert_eq!(FnvSum::new(64).unwrap().checksum(b""), "cbf29ce484222325");
+        assert_eq!(
+            FnvSum::new(128).unwrap().checksum(b""),
+            "6c62272e07bb014262b821756295c58d"
+        );
+    }
+
+    #[test]
+    fn test_a() {
+        assert_eq!(FnvSum::new(32).unwrap().checksum(b"a"), "e40c292c");
+        assert_eq!(FnvSum::new(64).unwrap().checksum(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_invalid_bit_length() {
+        assert!(FnvSum::new(256).is_err());
+    }
+}
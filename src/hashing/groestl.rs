@@ -0,0 +1,57 @@
+use crate::errors::GroestlSumError;
+use crate::hashing::Hasher;
+use digest::{Digest, DynDigest};
+use groestl::{Groestl224, Groestl256, Groestl384, Groestl512};
+
+pub struct GroestlSum {
+    /// Running digest, picked from the bit length of the checksum
+    hasher: Box<dyn DynDigest>,
+}
+
+impl Hasher for GroestlSum {
+    const VALID_VALUES: &'static [usize] = &[224, 256, 384, 512];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl GroestlSum {
+    pub fn new(checksum_bits: usize) -> Result<GroestlSum, GroestlSumError> {
+        let hasher: Box<dyn DynDigest> = match checksum_bits {
+            224 => Box::new(Groestl224::new()),
+            256 => Box::new(Groestl256::new()),
+            384 => Box::new(Groestl384::new()),
+            512 => Box::new(Groestl512::new()),
+            _ => return Err(GroestlSumError::InvalidChecksumType(checksum_bits)),
+        };
+
+        Ok(GroestlSum { hasher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            GroestlSum::new(256).unwrap().checksum(b""),
+            "1a52d11d550039be16107f9c58db9ebcc417f16f736adb2502567119f0083467"
+        );
+        assert_eq!(
+            GroestlSum::new(512).unwrap().checksum(b""),
+            "6d3ad29d279110eef3adbd66de2a0345a77baede1557f5d099fce0c03d6dc2ba8e6d4a6633dfbd66053c20faa87d1a11f39a7fbe4a6c2f009801370308fc4ad8"
+        );
+    }
+
+    #[test]
+    fn test_invalid_bit_length() {
+        assert!(GroestlSum::new(160).is_err());
+    }
+}
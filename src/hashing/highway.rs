@@ -0,0 +1,96 @@
+use crate::errors::HighwaySumError;
+use crate::hashing::Hasher;
+use highway::{HighwayHash, HighwayHasher, Key};
+
+/// Size of a HighwayHash key, in bytes.
+pub const KEY_SIZE: usize = 32;
+
+/// Keyed HighwayHash hasher with a 64-, 128- or 256-bit output.
+///
+/// Like SipHash, the output words are printed as little-endian bytes, which matches the byte
+/// representation used by the reference and Go implementations.
+pub struct HighwaySum {
+    /// Bit length of the checksum (64, 128 or 256)
+    checksum_bits: usize,
+
+    /// Running state
+    hasher: HighwayHasher,
+}
+
+impl Hasher for HighwaySum {
+    const VALID_VALUES: &'static [usize] = &[64, 128, 256];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.append(data);
+    }
+
+    fn finalize(self) -> String {
+        let words = match self.checksum_bits {
+            64 => vec![self.hasher.finalize64()],
+            128 => self.hasher.finalize128().to_vec(),
+            256 => self.hasher.finalize256().to_vec(),
+            _ => unreachable!(),
+        };
+
+        words
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+impl HighwaySum {
+    pub fn new(checksum_bits: usize, key: &[u8]) -> Result<HighwaySum, HighwaySumError> {
+        if !Self::VALID_VALUES.contains(&checksum_bits) {
+            return Err(HighwaySumError::InvalidChecksumType(checksum_bits));
+        }
+
+        if key.len() != KEY_SIZE {
+            return Err(HighwaySumError::InvalidKeyLength(key.len()));
+        }
+
+        let mut words = [0u64; 4];
+        for (word, chunk) in words.iter_mut().zip(key.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        Ok(HighwaySum {
+            checksum_bits,
+            hasher: HighwayHasher::new(Key(words)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Key([1, 2, 3, 4]), as used in the highway crate's examples
+    const KEY: [u8; KEY_SIZE] = [
+        1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0,
+        0, 0,
+    ];
+
+    #[test]
+    fn test_known_outputs() {
+        assert_eq!(
+            HighwaySum::new(64, &KEY).unwrap().checksum(&[255]),
+            "b2b2792d4df25878"
+        );
+        assert_eq!(
+            HighwaySum::new(128, &KEY).unwrap().checksum(&[255]),
+            "3c7fe762247d00bb1f99b316f9084522"
+        );
+        assert_eq!(
+            HighwaySum::new(256, &KEY).unwrap().checksum(&[255]),
+            "e170cdf7dbca61715e2f2be65d90c4aaa7fa3369932bb0078d9f235bc4cfefc8"
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(HighwaySum::new(32, &KEY).is_err());
+        assert!(HighwaySum::new(64, &KEY[..16]).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! Generic HMAC construction (RFC 2104), usable with any of the digest algorithms.
+
+use crate::hashing::DynHasher;
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// A streaming HMAC over another hash function.
+pub struct Hmac {
+    /// Hash of the inner padded key followed by the data
+    inner: Box<dyn DynHasher>,
+
+    /// Hash of the outer padded key, to be followed by the inner digest
+    outer: Box<dyn DynHasher>,
+}
+
+impl Hmac {
+    /// Starts computing an HMAC with the given `key`.
+    ///
+    /// `new_hash` must create a new instance of the hash function, producing hex-encoded digests,
+    /// and `block_size` is the block size (or rate, for sponge constructions) of that hash
+    /// function, in bytes.
+    pub fn new<E>(
+        block_size: usize,
+        key: &[u8],
+        mut new_hash: impl FnMut() -> Result<Box<dyn DynHasher>, E>,
+    ) -> Result<Hmac, E> {
+        // keys longer than a block are hashed first, shorter ones are padded with zeros
+        let mut block_key = if key.len() > block_size {
+            let mut hash = new_hash()?;
+            hash.update(key);
+            decode(hash.finalize())
+        } else {
+            key.to_vec()
+        };
+        block_key.resize(block_size, 0);
+
+        let mut inner = new_hash()?;
+        inner.update(&block_key.iter().map(|b| b ^ IPAD).collect::<Vec<u8>>());
+        let mut outer = new_hash()?;
+        outer.update(&block_key.iter().map(|b| b ^ OPAD).collect::<Vec<u8>>());
+
+        Ok(Hmac { inner, outer })
+    }
+}
+
+impl DynHasher for Hmac {
+    fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    fn finalize(self: Box<Self>) -> String {
+        let Hmac { inner, mut outer } = *self;
+        outer.update(&decode(inner.finalize()));
+        outer.finalize()
+    }
+}
+
+/// Decodes a digest produced by one of the hashers.
+fn decode(digest: String) -> Vec<u8> {
+    hex::decode(digest).expect("HMAC is only used with hex-encoded digests")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::shasum::ShaSum;
+    use std::convert::Infallible;
+
+    fn hmac_sha256(key: &[u8], data: &[u8]) -> String {
+        let mut hmac: Box<dyn DynHasher> = Box::new(
+            Hmac::new(64, key, || {
+                Ok::<_, Infallible>(Box::new(ShaSum::new(256).unwrap()) as Box<dyn DynHasher>)
+            })
+            .unwrap(),
+        );
+        hmac.update(data);
+        hmac.finalize()
+    }
+
+    #[test]
+    fn test_rfc4231_case_2() {
+        assert_eq!(
+            hmac_sha256(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_rfc4231_case_6() {
+        // key larger than the block size
+        let key = [0xaa; 131];
+        assert_eq!(
+            hmac_sha256(
+                &key,
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use crate::errors::K12SumError;
+use crate::hashing::Hasher;
+use k12::KangarooTwelve;
+use k12::digest::{ExtendableOutput, Update};
+
+/// KangarooTwelve hasher producing an output of arbitrary length (any non-zero multiple of 8 bits).
+pub struct K12Sum {
+    /// Bit length of the output
+    output_bits: usize,
+
+    /// Running state
+    hasher: KangarooTwelve<'static>,
+}
+
+impl Hasher for K12Sum {
+    // K12 is an XOF, see K12Sum::new for validation
+    const VALID_VALUES: &'static [usize] = &[];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        let mut buf = vec![0u8; self.output_bits / 8];
+        self.hasher.finalize_xof_into(&mut buf);
+
+        hex::encode(buf)
+    }
+}
+
+impl K12Sum {
+    pub fn new(output_bits: usize) -> Result<K12Sum, K12SumError> {
+        if output_bits == 0 || !output_bits.is_multiple_of(8) {
+            return Err(K12SumError::InvalidOutputLength(output_bits));
+        }
+
+        Ok(K12Sum {
+            output_bits,
+            hasher: KangarooTwelve::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        // from the KangarooTwelve reference paper
+        let checksum = K12Sum::new(256).unwrap();
+        assert_eq!(
+            checksum.checksum(b""),
+            "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+        );
+    }
+
+    #[test]
+    fn test_invalid_output_length() {
+        assert!(K12Sum::new(0).is_err());
+        assert!(K12Sum::new(100).is_err());
+    }
+}
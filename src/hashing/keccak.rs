@@ -0,0 +1,58 @@
+use crate::errors::KeccakSumError;
+use crate::hashing::Hasher;
+use digest::{Digest, DynDigest};
+use sha3::{Keccak224, Keccak256, Keccak384, Keccak512};
+
+/// Keccak hasher using the original (pre-FIPS 202) padding, as used by Ethereum.
+///
+/// This is *not* the same as SHA3: the two only differ in the domain separation byte, so their
+/// digests never match.
+pub struct KeccakSum {
+    /// Running digest, picked from the bit length of the checksum
+    hasher: Box<dyn DynDigest>,
+}
+
+impl Hasher for KeccakSum {
+    const VALID_VALUES: &'static [usize] = &[224, 256, 384, 512];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl KeccakSum {
+    pub fn new(checksum_bits: usize) -> Result<KeccakSum, KeccakSumError> {
+        let hasher: Box<dyn DynDigest> = match checksum_bits {
+            224 => Box::new(Keccak224::new()),
+            256 => Box::new(Keccak256::new()),
+            384 => Box::new(Keccak384::new()),
+            512 => Box::new(Keccak512::new()),
+            _ => return Err(KeccakSumError::InvalidChecksumType(checksum_bits)),
+        };
+
+        Ok(KeccakSum { hasher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak256_empty() {
+        // the well-known Ethereum empty hash, differs from SHA3-256("")
+        assert_eq!(
+            KeccakSum::new(256).unwrap().checksum(b""),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn test_invalid_bit_length() {
+        assert!(KeccakSum::new(160).is_err());
+    }
+}
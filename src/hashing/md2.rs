@@ -0,0 +1,50 @@
+use crate::hashing::Hasher;
+use digest::Digest;
+use md2::Md2;
+
+/// MD2 hasher. This is a legacy algorithm, only meant for comparing against old manifests.
+pub struct Md2Sum {
+    /// Running digest
+    hasher: Md2,
+}
+
+impl Hasher for Md2Sum {
+    const VALID_VALUES: &'static [usize] = &[128];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl Md2Sum {
+    pub fn new() -> Md2Sum {
+        Md2Sum { hasher: Md2::new() }
+    }
+}
+
+impl Default for Md2Sum {
+    fn default() -> Self {
+        Md2Sum::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc1319() {
+        assert_eq!(
+            Md2Sum::new().checksum(b""),
+            "8350e5a3e24c153df2275c9f80692773"
+        );
+        assert_eq!(
+            Md2Sum::new().checksum(b"abc"),
+            "da853b0d3f88d99b30283a69e6ded6bb"
+        );
+    }
+}
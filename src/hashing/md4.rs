@@ -0,0 +1,50 @@
+use crate::hashing::Hasher;
+use digest::Digest;
+use md4::Md4;
+
+/// MD4 hasher. This is a legacy algorithm, only meant for comparing against old manifests.
+pub struct Md4Sum {
+    /// Running digest
+    hasher: Md4,
+}
+
+impl Hasher for Md4Sum {
+    const VALID_VALUES: &'static [usize] = &[128];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl Md4Sum {
+    pub fn new() -> Md4Sum {
+        Md4Sum { hasher: Md4::new() }
+    }
+}
+
+impl Default for Md4Sum {
+    fn default() -> Self {
+        Md4Sum::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc1320() {
+        assert_eq!(
+            Md4Sum::new().checksum(b""),
+            "31d6cfe0d16ae931b73c59d7e0c089c0"
+        );
+        assert_eq!(
+            Md4Sum::new().checksum(b"abc"),
+            "a448017aaf21d8525fc10ae87aa6729d"
+        );
+    }
+}
@@ -1,21 +1,33 @@
 use crate::hashing::Hasher;
 
-pub struct Md5Sum<'a> {
-    /// Data to process
-    data: &'a [u8],
+pub struct Md5Sum {
+    /// Running digest
+    context: md5::Context,
 }
 
-impl Hasher for Md5Sum<'_> {
+impl Hasher for Md5Sum {
     const VALID_VALUES: &'static [usize] = &[128];
 
-    fn get_checksum(&self) -> String {
-        let a = md5::compute(self.data);
+    fn update(&mut self, data: &[u8]) {
+        self.context.consume(data);
+    }
+
+    fn finalize(self) -> String {
+        let a = self.context.compute();
         format!("{a:x}")
     }
 }
 
-impl<'a> Md5Sum<'a> {
-    pub fn new(data: &'a [u8]) -> Md5Sum<'a> {
-        Md5Sum { data }
+impl Md5Sum {
+    pub fn new() -> Md5Sum {
+        Md5Sum {
+            context: md5::Context::new(),
+        }
+    }
+}
+
+impl Default for Md5Sum {
+    fn default() -> Self {
+        Md5Sum::new()
     }
 }
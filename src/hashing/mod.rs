@@ -1,10 +1,163 @@
 pub mod blake2b;
+pub mod blake3;
+pub mod crc;
+pub mod ed2k;
+pub mod external;
+pub mod fnv;
+pub mod groestl;
+pub mod highway;
+pub mod hmac;
+pub mod k12;
+pub mod keccak;
+pub mod md2;
+pub mod md4;
 pub mod md5;
+pub mod murmur3;
+pub mod ripemd;
 pub mod sha3;
 pub mod shasum;
+pub mod siphash;
+pub mod skein;
+pub mod sm3;
+pub mod sp800_185;
+pub mod streebog;
+pub mod sum;
+pub mod tiger;
+pub mod whirlpool;
 
+use std::io::{self, ErrorKind, Read};
+
+/// Default size of the buffer used to feed streams to the hashers, in bytes.
+pub const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A streaming hasher: the data is fed with [`Hasher::update`], in as many pieces as needed, and
+/// the checksum is produced at the end by [`Hasher::finalize`].
 pub trait Hasher {
     const VALID_VALUES: &'static [usize];
 
-    fn get_checksum(&self) -> String;
+    /// Feeds more data to the hasher.
+    fn update(&mut self, data: &[u8]);
+
+    /// Consumes the hasher, returning the checksum.
+    fn finalize(self) -> String;
+
+    /// Hashes a whole buffer at once.
+    #[cfg(test)]
+    fn checksum(mut self, data: &[u8]) -> String
+    where
+        Self: Sized,
+    {
+        self.update(data);
+        self.finalize()
+    }
+}
+
+/// A [`Hasher`] that can be used as a trait object, so that the algorithm can be picked at runtime.
+pub trait DynHasher {
+    /// Feeds more data to the hasher.
+    fn update(&mut self, data: &[u8]);
+
+    /// Consumes the hasher, returning the checksum.
+    fn finalize(self: Box<Self>) -> String;
+}
+
+impl<H: Hasher> DynHasher for H {
+    fn update(&mut self, data: &[u8]) {
+        Hasher::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> String {
+        Hasher::finalize(*self)
+    }
+}
+
+/// Data to hash.
+pub enum Input<'a> {
+    /// Data that's already in memory, like a memory-mapped file, which is hashed without copying
+    Bytes(&'a [u8]),
+
+    /// A stream, read through a fixed-size buffer
+    Reader {
+        reader: &'a mut dyn Read,
+
+        /// Size of the buffer, in bytes
+        buffer_size: usize,
+    },
+}
+
+impl Input<'_> {
+    /// Size of the buffer used to read the input, in bytes.
+    pub fn buffer_size(&self) -> usize {
+        match self {
+            Input::Bytes(_) => DEFAULT_BUFFER_SIZE,
+            Input::Reader { buffer_size, .. } => *buffer_size,
+        }
+    }
+}
+
+impl Read for Input<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Input::Bytes(data) => data.read(buf),
+            Input::Reader { reader, .. } => reader.read(buf),
+        }
+    }
+}
+
+/// Feeds all of `input` to `hasher` and returns the checksum.
+pub fn hash_input<H: Hasher>(mut hasher: H, input: Input) -> io::Result<String> {
+    feed(input, |data| hasher.update(data))?;
+    Ok(hasher.finalize())
+}
+
+/// Passes all of `input` to `update`, in one piece for bytes in memory. Streams are read through a
+/// fixed-size buffer, so that memory use doesn't depend on the size of the input.
+pub fn feed(input: Input, mut update: impl FnMut(&[u8])) -> io::Result<()> {
+    let (reader, buffer_size) = match input {
+        Input::Bytes(data) => {
+            update(data);
+            return Ok(());
+        }
+        Input::Reader {
+            reader,
+            buffer_size,
+        } => (reader, buffer_size),
+    };
+
+    let mut buf = vec![0u8; buffer_size];
+
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => update(&buf[..n]),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::shasum::ShaSum;
+
+    #[test]
+    fn test_reader_matches_bytes() {
+        // spans several buffers, with a partial one at the end
+        let data: Vec<u8> = (0..DEFAULT_BUFFER_SIZE * 2 + 123)
+            .map(|i| i as u8)
+            .collect();
+        let expected = hash_input(ShaSum::new(256).unwrap(), Input::Bytes(&data)).unwrap();
+
+        for buffer_size in [7, DEFAULT_BUFFER_SIZE] {
+            let input = Input::Reader {
+                reader: &mut data.as_slice(),
+                buffer_size,
+            };
+            assert_eq!(
+                hash_input(ShaSum::new(256).unwrap(), input).unwrap(),
+                expected
+            );
+        }
+    }
 }
@@ -0,0 +1,205 @@
+use crate::errors::Murmur3SumError;
+use crate::hashing::Hasher;
+
+const C1_32: u32 = 0xcc9e_2d51;
+const C2_32: u32 = 0x1b87_3593;
+const C1_128: u64 = 0x87c3_7b91_1142_53d5;
+const C2_128: u64 = 0x4cf5_ad43_2745_937f;
+
+/// Running MurmurHash3 state, for each supported variant.
+enum Murmur3State {
+    X86_32(u32),
+    X64_128(u64, u64),
+}
+
+/// MurmurHash3 hasher, using the x86_32 variant for 32-bit output and x64_128 for 128-bit output.
+/// This is a fast, non-cryptographic hash.
+///
+/// The 32-bit output is printed as a number (like CRC32), while the 128-bit output is printed as
+/// the bytes written by the reference implementation (`h1` then `h2`, both little-endian).
+pub struct Murmur3Sum {
+    /// Running state, picked from the bit length of the checksum (32 or 128)
+    state: Murmur3State,
+
+    /// Bytes that don't fill a whole block yet
+    tail: Vec<u8>,
+
+    /// Number of bytes hashed so far
+    len: u64,
+}
+
+impl Hasher for Murmur3Sum {
+    const VALID_VALUES: &'static [usize] = &[32, 128];
+
+    fn update(&mut self, mut data: &[u8]) {
+        let block_size = self.block_size();
+        self.len += data.len() as u64;
+
+        if !self.tail.is_empty() {
+            let len = data.len().min(block_size - self.tail.len());
+            self.tail.extend_from_slice(&data[..len]);
+            data = &data[len..];
+
+            if self.tail.len() < block_size {
+                return;
+            }
+
+            let block = std::mem::take(&mut self.tail);
+            self.mix_block(&block);
+        }
+
+        let mut blocks = data.chunks_exact(block_size);
+        for block in &mut blocks {
+            self.mix_block(block);
+        }
+        self.tail.extend_from_slice(blocks.remainder());
+    }
+
+    fn finalize(self) -> String {
+        let mut tail = [0u8; 16];
+        tail[..self.tail.len()].copy_from_slice(&self.tail);
+
+        match self.state {
+            Murmur3State::X86_32(mut h) => {
+                if !self.tail.is_empty() {
+                    h ^= mix_k1_32(u32::from_le_bytes(tail[..4].try_into().unwrap()));
+                }
+
+                h ^= self.len as u32;
+                format!("{:08x}", fmix32(h))
+            }
+            Murmur3State::X64_128(mut h1, mut h2) => {
+                if self.tail.len() > 8 {
+                    h2 ^= mix_k2_128(u64::from_le_bytes(tail[8..].try_into().unwrap()));
+                }
+                if !self.tail.is_empty() {
+                    h1 ^= mix_k1_128(u64::from_le_bytes(tail[..8].try_into().unwrap()));
+                }
+
+                h1 ^= self.len;
+                h2 ^= self.len;
+                h1 = h1.wrapping_add(h2);
+                h2 = h2.wrapping_add(h1);
+                h1 = fmix64(h1);
+                h2 = fmix64(h2);
+                h1 = h1.wrapping_add(h2);
+                h2 = h2.wrapping_add(h1);
+
+                let mut out = h1.to_le_bytes().to_vec();
+                out.extend_from_slice(&h2.to_le_bytes());
+                hex::encode(out)
+            }
+        }
+    }
+}
+
+impl Murmur3Sum {
+    pub fn new(checksum_bits: usize, seed: u32) -> Result<Murmur3Sum, Murmur3SumError> {
+        let state = match checksum_bits {
+            32 => Murmur3State::X86_32(seed),
+            128 => Murmur3State::X64_128(u64::from(seed), u64::from(seed)),
+            _ => return Err(Murmur3SumError::InvalidChecksumType(checksum_bits)),
+        };
+
+        Ok(Murmur3Sum {
+            state,
+            tail: Vec::new(),
+            len: 0,
+        })
+    }
+
+    /// Size of the blocks processed by the variant, in bytes.
+    fn block_size(&self) -> usize {
+        match self.state {
+            Murmur3State::X86_32(_) => 4,
+            Murmur3State::X64_128(..) => 16,
+        }
+    }
+
+    /// Mixes a whole block into the state.
+    fn mix_block(&mut self, block: &[u8]) {
+        match &mut self.state {
+            Murmur3State::X86_32(h) => {
+                *h ^= mix_k1_32(u32::from_le_bytes(block.try_into().unwrap()));
+                *h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+            }
+            Murmur3State::X64_128(h1, h2) => {
+                *h1 ^= mix_k1_128(u64::from_le_bytes(block[..8].try_into().unwrap()));
+                *h1 = h1.rotate_left(27).wrapping_add(*h2);
+                *h1 = h1.wrapping_mul(5).wrapping_add(0x52dc_e729);
+
+                *h2 ^= mix_k2_128(u64::from_le_bytes(block[8..].try_into().unwrap()));
+                *h2 = h2.rotate_left(31).wrapping_add(*h1);
+                *h2 = h2.wrapping_mul(5).wrapping_add(0x3849_5ab5);
+            }
+        }
+    }
+}
+
+fn mix_k1_32(k: u32) -> u32 {
+    k.wrapping_mul(C1_32).rotate_left(15).wrapping_mul(C2_32)
+}
+
+fn mix_k1_128(k: u64) -> u64 {
+    k.wrapping_mul(C1_128).rotate_left(31).wrapping_mul(C2_128)
+}
+
+fn mix_k2_128(k: u64) -> u64 {
+    k.wrapping_mul(C2_128).rotate_left(33).wrapping_mul(C1_128)
+}
+
+/// Final avalanche of the 32-bit variant.
+fn fmix32(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+/// Final avalanche of the 128-bit variant.
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^ (k >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hello_world() {
+        let data = b"Hello, world!";
+
+        assert_eq!(Murmur3Sum::new(32, 0).unwrap().checksum(data), "c0363e43");
+        assert_eq!(
+            Murmur3Sum::new(128, 0).unwrap().checksum(data),
+            "df65d6d2d12d51f164c5f3a85066322c"
+        );
+    }
+
+    #[test]
+    fn test_split_updates() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+
+        for bits in [32, 128] {
+            let mut checksum = Murmur3Sum::new(bits, 0).unwrap();
+            for piece in data.chunks(5) {
+                checksum.update(piece);
+            }
+            assert_eq!(
+                checksum.finalize(),
+                Murmur3Sum::new(bits, 0).unwrap().checksum(data)
+            );
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        assert_eq!(Murmur3Sum::new(32, 0).unwrap().checksum(b""), "00000000");
+        assert_eq!(Murmur3Sum::new(32, 1).unwrap().checksum(b""), "514e28b7");
+    }
+}
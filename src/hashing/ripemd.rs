@@ -0,0 +1,60 @@
+use crate::errors::RipemdSumError;
+use crate::hashing::Hasher;
+use digest::{Digest, DynDigest};
+use ripemd::{Ripemd160, Ripemd256, Ripemd320};
+
+pub struct RipemdSum {
+    /// Running digest, picked from the bit length of the checksum (160, 256 or 320)
+    hasher: Box<dyn DynDigest>,
+}
+
+impl Hasher for RipemdSum {
+    const VALID_VALUES: &'static [usize] = &[160, 256, 320];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl RipemdSum {
+    pub fn new(checksum_bits: usize) -> Result<RipemdSum, RipemdSumError> {
+        let hasher: Box<dyn DynDigest> = match checksum_bits {
+            160 => Box::new(Ripemd160::new()),
+            256 => Box::new(Ripemd256::new()),
+            320 => Box::new(Ripemd320::new()),
+            _ => return Err(RipemdSumError::InvalidChecksumType(checksum_bits)),
+        };
+
+        Ok(RipemdSum { hasher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            RipemdSum::new(160).unwrap().checksum(b""),
+            "9c1185a5c5e9fc54612808977ee8f548b2258d31"
+        );
+        assert_eq!(
+            RipemdSum::new(256).unwrap().checksum(b""),
+            "02ba4c4e5f8ecd1877fc52d64d30e37a2d9774fb1e5d026380ae0168e3c5522d"
+        );
+        assert_eq!(
+            RipemdSum::new(320).unwrap().checksum(b""),
+            "22d65d5661536cdc75c1fdf5c6de7b41b9f27325ebc61e8557177d705a0ec880151c3a32a00899b8"
+        );
+    }
+
+    #[test]
+    fn test_invalid_bit_length() {
+        assert!(RipemdSum::new(128).is_err());
+    }
+}
@@ -1,54 +1,126 @@
-use crate::errors::Sha3SumError;
+use crate::errors::{Sha3SumError, ShakeSumError};
 use crate::hashing::Hasher;
-use sha3::{Digest, Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+use sha3::{
+    Digest, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128, Shake256,
+    digest::{DynDigest, ExtendableOutput, Update},
+};
 
-pub struct Sha3Sum<'a> {
-    /// Bit length of the checksum
-    checksum_type: usize,
-
-    /// Data to process
-    data: &'a [u8],
+pub struct Sha3Sum {
+    /// Running digest, picked from the bit length of the checksum
+    hasher: Box<dyn DynDigest>,
 }
 
-impl Hasher for Sha3Sum<'_> {
+impl Hasher for Sha3Sum {
     const VALID_VALUES: &'static [usize] = &[224, 256, 384, 512];
 
-    fn get_checksum(&self) -> String {
-        match self.checksum_type {
-            224 => {
-                let mut hasher = Sha3_224::new();
-                hasher.update(self.data);
-                format!("{:x}", hasher.finalize())
-            }
-            256 => {
-                let mut hasher = Sha3_256::new();
-                hasher.update(self.data);
-                format!("{:x}", hasher.finalize())
-            }
-            384 => {
-                let mut hasher = Sha3_384::new();
-                hasher.update(self.data);
-                format!("{:x}", hasher.finalize())
-            }
-            512 => {
-                let mut hasher = Sha3_512::new();
-                hasher.update(self.data);
-                format!("{:x}", hasher.finalize())
-            }
-            _ => unreachable!(),
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl Sha3Sum {
+    pub fn new(checksum_type: usize) -> Result<Sha3Sum, Sha3SumError> {
+        let hasher: Box<dyn DynDigest> = match checksum_type {
+            224 => Box::new(Sha3_224::new()),
+            256 => Box::new(Sha3_256::new()),
+            384 => Box::new(Sha3_384::new()),
+            512 => Box::new(Sha3_512::new()),
+            _ => return Err(Sha3SumError::InvalidChecksumType(checksum_type)),
+        };
+
+        Ok(Sha3Sum { hasher })
+    }
+}
+
+/// The two SHAKE extendable-output functions defined in FIPS 202.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ShakeVariant {
+    Shake128,
+    Shake256,
+}
+
+/// Running state of either SHAKE function.
+enum ShakeState {
+    Shake128(Shake128),
+    Shake256(Shake256),
+}
+
+/// SHAKE hasher producing an output of arbitrary length (any non-zero multiple of 8 bits).
+pub struct ShakeSum {
+    /// Bit length of the output
+    output_bits: usize,
+
+    /// Running state, picked from the variant
+    state: ShakeState,
+}
+
+impl Hasher for ShakeSum {
+    // XOFs have no fixed set of lengths, see ShakeSum::new for validation
+    const VALID_VALUES: &'static [usize] = &[];
+
+    fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            ShakeState::Shake128(hasher) => Update::update(hasher, data),
+            ShakeState::Shake256(hasher) => Update::update(hasher, data),
+        }
+    }
+
+    fn finalize(self) -> String {
+        let mut buf = vec![0u8; self.output_bits / 8];
+
+        match self.state {
+            ShakeState::Shake128(hasher) => hasher.finalize_xof_into(&mut buf),
+            ShakeState::Shake256(hasher) => hasher.finalize_xof_into(&mut buf),
         }
+
+        hex::encode(buf)
     }
 }
 
-impl<'a> Sha3Sum<'a> {
-    pub fn new(checksum_type: usize, data: &'a [u8]) -> Result<Sha3Sum<'a>, Sha3SumError> {
-        if !Self::VALID_VALUES.contains(&(checksum_type)) {
-            return Err(Sha3SumError::InvalidChecksumType(checksum_type));
+impl ShakeSum {
+    pub fn new(variant: ShakeVariant, output_bits: usize) -> Result<ShakeSum, ShakeSumError> {
+        if output_bits == 0 || !output_bits.is_multiple_of(8) {
+            return Err(ShakeSumError::InvalidOutputLength(output_bits));
         }
 
-        Ok(Sha3Sum {
-            checksum_type,
-            data,
-        })
+        let state = match variant {
+            ShakeVariant::Shake128 => ShakeState::Shake128(Shake128::default()),
+            ShakeVariant::Shake256 => ShakeState::Shake256(Shake256::default()),
+        };
+
+        Ok(ShakeSum { output_bits, state })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shake_empty() {
+        let shake128 = ShakeSum::new(ShakeVariant::Shake128, 256).unwrap();
+        assert_eq!(
+            shake128.checksum(b""),
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+        );
+
+        let shake256 = ShakeSum::new(ShakeVariant::Shake256, 512).unwrap();
+        assert_eq!(
+            shake256.checksum(b""),
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be"
+        );
+    }
+
+    #[test]
+    fn test_shake_output_length() {
+        let short = ShakeSum::new(ShakeVariant::Shake128, 64).unwrap();
+        assert_eq!(short.checksum(b""), "7f9c2ba4e88f827d");
+
+        assert!(ShakeSum::new(ShakeVariant::Shake128, 0).is_err());
+        assert!(ShakeSum::new(ShakeVariant::Shake256, 12).is_err());
     }
 }
@@ -1,53 +1,38 @@
 use crate::errors::ShaSumError;
 use crate::hashing::Hasher;
-use digest::Digest;
+use digest::{Digest, DynDigest};
 use sha1::Sha1;
 use sha2::{Sha224, Sha256, Sha384, Sha512};
 
-/// Hashes the data using the specified checksum type
-macro_rules! hash_match {
-    ($bits:expr, $data:expr) => {
-        match $bits {
-            160 => hex::encode(Sha1::digest($data)),
-            224 => hex::encode(Sha224::digest($data)),
-            256 => hex::encode(Sha256::digest($data)),
-            384 => hex::encode(Sha384::digest($data)),
-            512 => hex::encode(Sha512::digest($data)),
-            _ => unreachable!(),
-        }
-    };
+pub struct ShaSum {
+    /// Running digest, picked from the bit length of the checksum (160, 224, 256, 384, or 512)
+    hasher: Box<dyn DynDigest>,
 }
 
-pub struct ShaSum<'a> {
-    /// Bit length of the checksum (160, 224, 256, 384, or 512)
-    checksum_bits: usize,
-
-    /// Data to process
-    data: &'a [u8],
-}
-
-impl Hasher for ShaSum<'_> {
+impl Hasher for ShaSum {
     const VALID_VALUES: &'static [usize] = &[160, 224, 256, 384, 512];
 
-    fn get_checksum(&self) -> String {
-        match self.checksum_bits {
-            bits @ (160 | 224 | 256 | 384 | 512) => hash_match!(bits, self.data),
-            _ => unreachable!(),
-        }
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
     }
 }
 
-impl<'a> ShaSum<'a> {
-    pub fn new(checksum_type: usize, data: &'a [u8]) -> Result<ShaSum<'a>, ShaSumError> {
-        let bits = checksum_type;
-        if !Self::VALID_VALUES.contains(&bits) {
-            return Err(ShaSumError::InvalidChecksumType(checksum_type));
-        }
-
-        Ok(ShaSum {
-            checksum_bits: bits,
-            data,
-        })
+impl ShaSum {
+    pub fn new(checksum_type: usize) -> Result<ShaSum, ShaSumError> {
+        let hasher: Box<dyn DynDigest> = match checksum_type {
+            160 => Box::new(Sha1::new()),
+            224 => Box::new(Sha224::new()),
+            256 => Box::new(Sha256::new()),
+            384 => Box::new(Sha384::new()),
+            512 => Box::new(Sha512::new()),
+            _ => return Err(ShaSumError::InvalidChecksumType(checksum_type)),
+        };
+
+        Ok(ShaSum { hasher })
     }
 }
 
@@ -59,12 +44,12 @@ mod tests {
     fn test_sha512sum() {
         let data = b"i use arch btw\n";
 
-        let checksummer = ShaSum::new(512, data).unwrap();
+        let checksummer = ShaSum::new(512).unwrap();
 
         // echo 'i use arch btw' | sha512sum -b
         let expected_checksum = "2ddbe9f9af5a630d3734ce469fac19088e8d0242541768630777de5c56dc4053d346a67527cb95de3ab094d6862f393392ba26bed459d9ad149b423aeae552a2"
             .to_owned();
-        let actual_checksum = checksummer.get_checksum();
+        let actual_checksum = checksummer.checksum(data);
         assert_eq!(actual_checksum, expected_checksum);
     }
 
@@ -72,13 +57,13 @@ mod tests {
     fn test_sha384sum() {
         let data = b"i use arch btw\n";
 
-        let checksummer = ShaSum::new(384, data).unwrap();
+        let checksummer = ShaSum::new(384).unwrap();
 
         let expected_checksum =
             "263b578ab61613a5dff5b9c2aadf9601250e316aca387a5edb9b01da1aeb431f2b6e718b86e1b293adf51a14d058dceb"
                 .to_owned();
 
-        let actual_checksum = checksummer.get_checksum();
+        let actual_checksum = checksummer.checksum(data);
         assert_eq!(actual_checksum, expected_checksum);
     }
 
@@ -86,12 +71,12 @@ mod tests {
     fn test_sha256sum() {
         let data = b"i use arch btw\n";
 
-        let checksummer = ShaSum::new(256, data).unwrap();
+        let checksummer = ShaSum::new(256).unwrap();
 
         let expected_checksum =
             "80799b90f4c070668b52df31830b60ef767bb039000eec4266f285d498002bb5".to_owned();
 
-        let actual_checksum = checksummer.get_checksum();
+        let actual_checksum = checksummer.checksum(data);
         assert_eq!(actual_checksum, expected_checksum);
     }
 
@@ -99,12 +84,12 @@ mod tests {
     fn test_sha224sum() {
         let data = b"i use arch btw\n";
 
-        let checksummer = ShaSum::new(224, data).unwrap();
+        let checksummer = ShaSum::new(224).unwrap();
 
         let expected_checksum =
             "990fe822fd00f196671004f5aeebf50d073da8de3d8fc45f466e7092".to_owned();
 
-        let actual_checksum = checksummer.get_checksum();
+        let actual_checksum = checksummer.checksum(data);
         assert_eq!(actual_checksum, expected_checksum);
     }
 
@@ -112,11 +97,11 @@ mod tests {
     fn test_sha1sum() {
         let data = b"i use arch btw\n";
 
-        let checksummer = ShaSum::new(160, data).unwrap();
+        let checksummer = ShaSum::new(160).unwrap();
 
         let expected_checksum = "821609590ef05d00b20c5f4c5a28c56627480eb7".to_owned();
 
-        let actual_checksum = checksummer.get_checksum();
+        let actual_checksum = checksummer.checksum(data);
         assert_eq!(actual_checksum, expected_checksum);
     }
 }
@@ -0,0 +1,97 @@
+use crate::errors::SipHashSumError;
+use crate::hashing::Hasher;
+use siphasher::{
+    sip::SipHasher24,
+    sip128::{Hasher128, SipHasher24 as SipHasher24_128},
+};
+use std::hash::Hasher as _;
+
+/// Size of a SipHash key, in bytes.
+pub const KEY_SIZE: usize = 16;
+
+/// Running SipHash state, for each supported output length.
+enum SipHashState {
+    SipHash64(SipHasher24),
+    SipHash128(SipHasher24_128),
+}
+
+/// Keyed SipHash-2-4 hasher with a 64- or 128-bit output.
+///
+/// The digest is printed as the little-endian bytes of the output, which is what the reference
+/// implementation's test vectors use.
+pub struct SipHashSum {
+    /// Running state, picked from the bit length of the checksum (64 or 128)
+    state: SipHashState,
+}
+
+impl Hasher for SipHashSum {
+    const VALID_VALUES: &'static [usize] = &[64, 128];
+
+    fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            SipHashState::SipHash64(hasher) => hasher.write(data),
+            SipHashState::SipHash128(hasher) => hasher.write(data),
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self.state {
+            SipHashState::SipHash64(hasher) => hex::encode(hasher.finish().to_le_bytes()),
+            SipHashState::SipHash128(hasher) => hex::encode(hasher.finish128().as_bytes()),
+        }
+    }
+}
+
+impl SipHashSum {
+    pub fn new(checksum_bits: usize, key: &[u8]) -> Result<SipHashSum, SipHashSumError> {
+        if !Self::VALID_VALUES.contains(&checksum_bits) {
+            return Err(SipHashSumError::InvalidChecksumType(checksum_bits));
+        }
+
+        let key: &[u8; KEY_SIZE] = key
+            .try_into()
+            .map_err(|_| SipHashSumError::InvalidKeyLength(key.len()))?;
+
+        let state = match checksum_bits {
+            64 => SipHashState::SipHash64(SipHasher24::new_with_key(key)),
+            _ => SipHashState::SipHash128(SipHasher24_128::new_with_key(key)),
+        };
+
+        Ok(SipHashSum { state })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // key and messages used by the reference implementation's test vectors
+    const KEY: [u8; KEY_SIZE] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f,
+    ];
+
+    #[test]
+    fn test_reference_vectors() {
+        assert_eq!(
+            SipHashSum::new(64, &KEY).unwrap().checksum(b""),
+            "310e0edd47db6f72"
+        );
+        assert_eq!(
+            SipHashSum::new(128, &KEY).unwrap().checksum(b""),
+            "a3817f04ba25a8e66df67214c7550293"
+        );
+
+        let message: Vec<u8> = (0..15).collect();
+        assert_eq!(
+            SipHashSum::new(64, &KEY).unwrap().checksum(&message),
+            "e545be4961ca29a1"
+        );
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(SipHashSum::new(32, &KEY).is_err());
+        assert!(SipHashSum::new(64, &KEY[..8]).is_err());
+    }
+}
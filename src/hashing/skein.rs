@@ -0,0 +1,122 @@
+use crate::errors::SkeinSumError;
+use crate::hashing::Hasher;
+use digest::{
+    Digest, DynDigest,
+    consts::{U16, U20, U28, U32, U48, U64, U128},
+};
+use skein::{Skein256, Skein512, Skein1024};
+
+/// Creates a digest with the given Skein state size, for every supported output length
+macro_rules! skein_match {
+    ($skein:ident, $bits:expr, [$($size:literal => $len:ty),+ $(,)?]) => {
+        match $bits {
+            $($size => Box::new($skein::<$len>::new()),)+
+            _ => unreachable!(),
+        }
+    };
+}
+
+/// The three Skein internal state sizes.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SkeinVariant {
+    Skein256,
+    Skein512,
+    Skein1024,
+}
+
+impl SkeinVariant {
+    /// Output lengths supported for this state size, in bits.
+    pub const fn valid_values(self) -> &'static [usize] {
+        match self {
+            SkeinVariant::Skein256 => &[128, 160, 224, 256],
+            SkeinVariant::Skein512 => SkeinSum::VALID_VALUES,
+            SkeinVariant::Skein1024 => &[384, 512, 1024],
+        }
+    }
+
+    /// Internal state size, in bits.
+    pub const fn state_bits(self) -> usize {
+        match self {
+            SkeinVariant::Skein256 => 256,
+            SkeinVariant::Skein512 => 512,
+            SkeinVariant::Skein1024 => 1024,
+        }
+    }
+}
+
+pub struct SkeinSum {
+    /// Running digest, picked from the state size and output length
+    hasher: Box<dyn DynDigest>,
+}
+
+impl Hasher for SkeinSum {
+    // lengths supported by Skein-512, see SkeinVariant::valid_values for the other state sizes
+    const VALID_VALUES: &'static [usize] = &[128, 160, 224, 256, 384, 512];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl SkeinSum {
+    pub fn new(variant: SkeinVariant, output_bits: usize) -> Result<SkeinSum, SkeinSumError> {
+        if !variant.valid_values().contains(&output_bits) {
+            return Err(SkeinSumError::InvalidOutputLength {
+                state_bits: variant.state_bits(),
+                output_bits,
+            });
+        }
+
+        let hasher: Box<dyn DynDigest> = match variant {
+            SkeinVariant::Skein256 => skein_match!(
+                Skein256,
+                output_bits,
+                [128 => U16, 160 => U20, 224 => U28, 256 => U32]
+            ),
+            SkeinVariant::Skein512 => skein_match!(
+                Skein512,
+                output_bits,
+                [128 => U16, 160 => U20, 224 => U28, 256 => U32, 384 => U48, 512 => U64]
+            ),
+            SkeinVariant::Skein1024 => skein_match!(
+                Skein1024,
+                output_bits,
+                [384 => U48, 512 => U64, 1024 => U128]
+            ),
+        };
+
+        Ok(SkeinSum { hasher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            SkeinSum::new(SkeinVariant::Skein256, 256)
+                .unwrap()
+                .checksum(b""),
+            "c8877087da56e072870daa843f176e9453115929094c3a40c463a196c29bf7ba"
+        );
+        assert_eq!(
+            SkeinSum::new(SkeinVariant::Skein512, 512)
+                .unwrap()
+                .checksum(b""),
+            "bc5b4c50925519c290cc634277ae3d6257212395cba733bbad37a4af0fa06af41fca7903d06564fea7a2d3730dbdb80c1f85562dfcc070334ea4d1d9e72cba7a"
+        );
+    }
+
+    #[test]
+    fn test_invalid_output_length() {
+        assert!(SkeinSum::new(SkeinVariant::Skein256, 512).is_err());
+        assert!(SkeinSum::new(SkeinVariant::Skein1024, 256).is_err());
+        assert!(SkeinSum::new(SkeinVariant::Skein1024, 1024).is_ok());
+    }
+}
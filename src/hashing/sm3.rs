@@ -0,0 +1,47 @@
+use crate::hashing::Hasher;
+use digest::Digest;
+use sm3::Sm3;
+
+/// SM3 hasher, as specified by GB/T 32905-2016 (fixed 256-bit output).
+pub struct Sm3Sum {
+    /// Running digest
+    hasher: Sm3,
+}
+
+impl Hasher for Sm3Sum {
+    const VALID_VALUES: &'static [usize] = &[256];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl Sm3Sum {
+    pub fn new() -> Sm3Sum {
+        Sm3Sum { hasher: Sm3::new() }
+    }
+}
+
+impl Default for Sm3Sum {
+    fn default() -> Self {
+        Sm3Sum::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abc() {
+        // example 1 from the standard
+        assert_eq!(
+            Sm3Sum::new().checksum(b"abc"),
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+        );
+    }
+}
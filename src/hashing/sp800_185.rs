@@ -0,0 +1,185 @@
+use crate::errors::Sp800185SumError;
+use crate::hashing::Hasher;
+use tiny_keccak::{CShake, Hasher as _, Kmac, TupleHash};
+
+/// The NIST SP 800-185 derived functions.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Sp800185Function {
+    CShake128,
+    CShake256,
+    Kmac128,
+    Kmac256,
+    TupleHash128,
+    TupleHash256,
+}
+
+impl Sp800185Function {
+    /// Whether the function is a MAC, and therefore requires a key.
+    pub const fn is_keyed(self) -> bool {
+        matches!(self, Sp800185Function::Kmac128 | Sp800185Function::Kmac256)
+    }
+}
+
+/// Largest input TupleHash should be used on, 1 GiB, as it's kept in memory until the hash is
+/// finalized.
+pub const TUPLEHASH_MAX_INPUT: u64 = 1 << 30;
+
+/// Running state of the SP 800-185 functions.
+enum Sp800185State {
+    CShake(CShake),
+    Kmac(Kmac),
+    /// TupleHash encodes the length of each element before its contents, so the element has to
+    /// be buffered until it's complete
+    TupleHash(TupleHash, Vec<u8>),
+}
+
+impl Sp800185State {
+    fn new(function: Sp800185Function, key: &[u8], customization: &[u8]) -> Sp800185State {
+        match function {
+            Sp800185Function::CShake128 => Sp800185State::CShake(CShake::v128(&[], customization)),
+            Sp800185Function::CShake256 => Sp800185State::CShake(CShake::v256(&[], customization)),
+            Sp800185Function::Kmac128 => Sp800185State::Kmac(Kmac::v128(key, customization)),
+            Sp800185Function::Kmac256 => Sp800185State::Kmac(Kmac::v256(key, customization)),
+            Sp800185Function::TupleHash128 => {
+                Sp800185State::TupleHash(TupleHash::v128(customization), Vec::new())
+            }
+            Sp800185Function::TupleHash256 => {
+                Sp800185State::TupleHash(TupleHash::v256(customization), Vec::new())
+            }
+        }
+    }
+}
+
+/// Hasher for the SP 800-185 functions, producing an output of arbitrary length (any non-zero
+/// multiple of 8 bits).
+///
+/// The function name `N` of cSHAKE is always empty, as it's reserved for functions defined by
+/// NIST. TupleHash treats the data as a tuple with a single element, which is kept in memory, so it
+/// shouldn't be used on inputs larger than [`TUPLEHASH_MAX_INPUT`].
+///
+/// The key and customization string must be set before any data is fed to the hasher, as
+/// changing them resets the running state.
+pub struct Sp800185Sum {
+    /// Which function to use
+    function: Sp800185Function,
+
+    /// Bit length of the output
+    output_bits: usize,
+
+    /// Customization string `S`
+    customization: Vec<u8>,
+
+    /// Key, only used by KMAC
+    key: Vec<u8>,
+
+    /// Running state, created from the parameters above
+    state: Sp800185State,
+}
+
+impl Hasher for Sp800185Sum {
+    // these are XOFs, see Sp800185Sum::new for validation
+    const VALID_VALUES: &'static [usize] = &[];
+
+    fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            Sp800185State::CShake(hasher) => hasher.update(data),
+            Sp800185State::Kmac(hasher) => hasher.update(data),
+            Sp800185State::TupleHash(_, element) => element.extend_from_slice(data),
+        }
+    }
+
+    fn finalize(self) -> String {
+        let mut buf = vec![0u8; self.output_bits / 8];
+
+        match self.state {
+            Sp800185State::CShake(hasher) => hasher.finalize(&mut buf),
+            Sp800185State::Kmac(hasher) => hasher.finalize(&mut buf),
+            Sp800185State::TupleHash(mut hasher, element) => {
+                hasher.update(&element);
+                hasher.finalize(&mut buf);
+            }
+        }
+
+        hex::encode(buf)
+    }
+}
+
+impl Sp800185Sum {
+    pub fn new(
+        function: Sp800185Function,
+        output_bits: usize,
+    ) -> Result<Sp800185Sum, Sp800185SumError> {
+        if output_bits == 0 || !output_bits.is_multiple_of(8) {
+            return Err(Sp800185SumError::InvalidOutputLength(output_bits));
+        }
+
+        Ok(Sp800185Sum {
+            function,
+            output_bits,
+            customization: Vec::new(),
+            key: Vec::new(),
+            state: Sp800185State::new(function, &[], &[]),
+        })
+    }
+
+    /// Sets the customization string `S`.
+    pub fn with_customization(mut self, customization: &[u8]) -> Self {
+        self.customization = customization.to_vec();
+        self.state = Sp800185State::new(self.function, &self.key, &self.customization);
+        self
+    }
+
+    /// Sets the KMAC key.
+    pub fn with_key(mut self, key: &[u8]) -> Self {
+        self.key = key.to_vec();
+        self.state = Sp800185State::new(self.function, &self.key, &self.customization);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // inputs from the NIST SP 800-185 examples
+    const DATA: &[u8] = &[0x00, 0x01, 0x02, 0x03];
+
+    #[test]
+    fn test_cshake128_sample_1() {
+        let checksum = Sp800185Sum::new(Sp800185Function::CShake128, 256)
+            .unwrap()
+            .with_customization(b"Email Signature");
+        assert_eq!(
+            checksum.checksum(DATA),
+            "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"
+        );
+    }
+
+    #[test]
+    fn test_kmac128_samples() {
+        let key: Vec<u8> = (0x40..=0x5f).collect();
+
+        let checksum = Sp800185Sum::new(Sp800185Function::Kmac128, 256)
+            .unwrap()
+            .with_key(&key);
+        assert_eq!(
+            checksum.checksum(DATA),
+            "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e"
+        );
+
+        let checksum = Sp800185Sum::new(Sp800185Function::Kmac128, 256)
+            .unwrap()
+            .with_key(&key)
+            .with_customization(b"My Tagged Application");
+        assert_eq!(
+            checksum.checksum(DATA),
+            "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5"
+        );
+    }
+
+    #[test]
+    fn test_invalid_output_length() {
+        assert!(Sp800185Sum::new(Sp800185Function::TupleHash128, 0).is_err());
+        assert!(Sp800185Sum::new(Sp800185Function::Kmac256, 9).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use crate::errors::StreebogSumError;
+use crate::hashing::Hasher;
+use digest::{Digest, DynDigest};
+use streebog::{Streebog256, Streebog512};
+
+/// Streebog (GOST R 34.11-2012) hasher.
+pub struct StreebogSum {
+    /// Running digest, picked from the bit length of the checksum (256 or 512)
+    hasher: Box<dyn DynDigest>,
+}
+
+impl Hasher for StreebogSum {
+    const VALID_VALUES: &'static [usize] = &[256, 512];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl StreebogSum {
+    pub fn new(checksum_bits: usize) -> Result<StreebogSum, StreebogSumError> {
+        let hasher: Box<dyn DynDigest> = match checksum_bits {
+            256 => Box::new(Streebog256::new()),
+            512 => Box::new(Streebog512::new()),
+            _ => return Err(StreebogSumError::InvalidChecksumType(checksum_bits)),
+        };
+
+        Ok(StreebogSum { hasher })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            StreebogSum::new(256).unwrap().checksum(b""),
+            "3f539a213e97c802cc229d474c6aa32a825a360b2a933a949fd925208d9ce1bb"
+        );
+        assert_eq!(
+            StreebogSum::new(512).unwrap().checksum(b""),
+            "8e945da209aa869f0455928529bcae4679e9873ab707b55315f56ceb98bef0a7362f715528356ee83cda5f2aac4c6ad2ba3a715c1bcd81cb8e9f90bf4c1c1a8a"
+        );
+    }
+}
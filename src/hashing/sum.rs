@@ -0,0 +1,98 @@
+use crate::hashing::Hasher;
+
+/// 16-bit BSD rotating checksum, as printed by `sum -r`.
+///
+/// The checksum is rendered the way `sum` prints it: the zero-padded checksum followed by the
+/// number of 1024-byte blocks (e.g. `53615     1`).
+#[derive(Default)]
+pub struct BsdSum {
+    /// Running checksum
+    checksum: u16,
+
+    /// Number of bytes hashed so far
+    len: u64,
+}
+
+impl Hasher for BsdSum {
+    const VALID_VALUES: &'static [usize] = &[16];
+
+    fn update(&mut self, data: &[u8]) {
+        self.checksum = data.iter().fold(self.checksum, |checksum, &b| {
+            checksum.rotate_right(1).wrapping_add(u16::from(b))
+        });
+        self.len += data.len() as u64;
+    }
+
+    fn finalize(self) -> String {
+        format!("{:05} {:5}", self.checksum, self.len.div_ceil(1024))
+    }
+}
+
+impl BsdSum {
+    pub fn new() -> BsdSum {
+        BsdSum::default()
+    }
+}
+
+/// 16-bit System V checksum, as printed by `sum -s`.
+///
+/// The checksum is rendered the way `sum` prints it: the checksum followed by the number of
+/// 512-byte blocks (e.g. `477 1`).
+#[derive(Default)]
+pub struct SysvSum {
+    /// Running sum of all the bytes
+    total: u32,
+
+    /// Number of bytes hashed so far
+    len: u64,
+}
+
+impl Hasher for SysvSum {
+    const VALID_VALUES: &'static [usize] = &[16];
+
+    fn update(&mut self, data: &[u8]) {
+        self.total = data
+            .iter()
+            .fold(self.total, |total, &b| total.wrapping_add(u32::from(b)));
+        self.len += data.len() as u64;
+    }
+
+    fn finalize(self) -> String {
+        let r = (self.total & 0xffff) + (self.total >> 16);
+        let checksum = (r & 0xffff) + (r >> 16);
+
+        format!("{checksum} {}", self.len.div_ceil(512))
+    }
+}
+
+impl SysvSum {
+    pub fn new() -> SysvSum {
+        SysvSum::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bsd_sum() {
+        // printf 123456789 | sum -r
+        assert_eq!(BsdSum::new().checksum(b"123456789"), "53615     1");
+        assert_eq!(BsdSum::new().checksum(b""), "00000     0");
+    }
+
+    #[test]
+    fn test_sysv_sum() {
+        // printf 123456789 | sum -s
+        assert_eq!(SysvSum::new().checksum(b"123456789"), "477 1");
+        assert_eq!(SysvSum::new().checksum(b""), "0 0");
+    }
+
+    #[test]
+    fn test_block_counts() {
+        let data = vec![0u8; 1025];
+        assert!(BsdSum::new().checksum(&data).ends_with("    2"));
+        assert!(SysvSum::new().checksum(&data).ends_with(" 3"));
+    }
+}
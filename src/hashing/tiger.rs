@@ -0,0 +1,59 @@
+use crate::hashing::Hasher;
+use digest::{Digest, DynDigest};
+use tiger::{Tiger, Tiger2};
+
+/// The two Tiger padding variants. They only differ in the padding byte (0x01 vs 0x80).
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TigerVariant {
+    Tiger,
+    Tiger2,
+}
+
+/// Flat (non-tree) Tiger hasher with a fixed 192-bit output.
+///
+/// Tiger Tree Hashes (TTH) are built on top of the same compression function, so they can be
+/// added as another variant once tree hashing is supported.
+pub struct TigerSum {
+    /// Running digest, picked from the padding variant
+    hasher: Box<dyn DynDigest>,
+}
+
+impl Hasher for TigerSum {
+    const VALID_VALUES: &'static [usize] = &[192];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl TigerSum {
+    pub fn new(variant: TigerVariant) -> TigerSum {
+        let hasher: Box<dyn DynDigest> = match variant {
+            TigerVariant::Tiger => Box::new(Tiger::new()),
+            TigerVariant::Tiger2 => Box::new(Tiger2::new()),
+        };
+
+        TigerSum { hasher }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            TigerSum::new(TigerVariant::Tiger).checksum(b""),
+            "3293ac630c13f0245f92bbb1766e16167a4e58492dde73f3"
+        );
+        assert_eq!(
+            TigerSum::new(TigerVariant::Tiger2).checksum(b""),
+            "4441be75f6018773c206c22745374b924aa8313fef919f41"
+        );
+    }
+}
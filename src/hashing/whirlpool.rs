@@ -0,0 +1,47 @@
+use crate::hashing::Hasher;
+use digest::Digest;
+use whirlpool::Whirlpool;
+
+pub struct WhirlpoolSum {
+    /// Running digest
+    hasher: Whirlpool,
+}
+
+impl Hasher for WhirlpoolSum {
+    const VALID_VALUES: &'static [usize] = &[512];
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    fn finalize(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl WhirlpoolSum {
+    pub fn new() -> WhirlpoolSum {
+        WhirlpoolSum {
+            hasher: Whirlpool::new(),
+        }
+    }
+}
+
+impl Default for WhirlpoolSum {
+    fn default() -> Self {
+        WhirlpoolSum::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(
+            WhirlpoolSum::new().checksum(b""),
+            "19fa61d75522a4669b44e39c1d2e1726c530232130d407f89afee0964997f7a73e83be698b288febcf88e3e03c4f0757ea8964e59b63d93708b138cc42a66eb3"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Opening the files to hash.
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod uring;
+
+use crate::throttle::RateLimiter;
+use memmap2::{Advice, Mmap};
+use std::{fs::File, io, path::Path};
+use steadyhash::hashing::{DEFAULT_BUFFER_SIZE, Input};
+
+/// How files are read.
+pub struct InputOptions {
+    /// Whether to memory-map regular files instead of reading them through a buffer
+    pub mmap: bool,
+
+    /// Size of the buffer used to read streamed files, in bytes
+    pub buffer_size: usize,
+
+    /// Whether to evict files from the page cache once they've been hashed (Linux only)
+    pub drop_cache: bool,
+
+    /// Limit on the combined read rate of every file
+    pub limiter: Option<RateLimiter>,
+
+    /// Whether to read regular files through io_uring
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    pub io_uring: bool,
+}
+
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions {
+            mmap: false,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            drop_cache: false,
+            limiter: None,
+            #[cfg(all(target_os = "linux", feature = "io-uring"))]
+            io_uring: false,
+        }
+    }
+}
+
+/// A file opened for hashing.
+///
+/// The kernel is told that the file is going to be read sequentially, and with `drop_cache` it's
+/// also asked to evict the file from the page cache once it's closed, so that bulk runs don't push
+/// out the cache of other processes.
+pub struct OpenedFile {
+    /// Reader queueing reads of the file through io_uring, if enabled. It refers to `file`, so
+    /// it's declared first to be dropped first.
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    uring: Option<uring::UringReader>,
+
+    file: File,
+
+    /// Mapping of the file, if it's a memory-mapped regular file
+    map: Option<Mmap>,
+
+    /// Size of the buffer used to read the file when it isn't mapped, in bytes
+    buffer_size: usize,
+
+    /// Whether to evict the file from the page cache once it's closed
+    drop_cache: bool,
+}
+
+impl OpenedFile {
+    /// Opens the file at `path`. Pipes, devices and other special files are always streamed, as
+    /// they can't be memory-mapped or read at arbitrary offsets through io_uring.
+    pub fn open(path: &Path, options: &InputOptions) -> io::Result<OpenedFile> {
+        let file = File::open(path)?;
+        let mut map = None;
+
+        if options.mmap {
+            let metadata = file.metadata()?;
+
+            // empty files can't be mapped on every platform, and are trivial to read anyway
+            if metadata.is_file() && metadata.len() > 0 {
+                // SAFETY: the mapping is only read while hashing. If the file is truncated or
+                // modified by another process in the meantime, the checksum is meaningless anyway,
+                // which is the same trade-off made by other tools hashing mapped files.
+                map = unsafe { Mmap::map(&file) }.ok();
+            }
+        }
+
+        // these are only hints, so failures don't matter
+        match &map {
+            Some(map) => {
+                let _ = map.advise(Advice::Sequential);
+            }
+            None => {
+                #[cfg(target_os = "linux")]
+                fadvise(&file, libc::POSIX_FADV_SEQUENTIAL);
+            }
+        }
+
+        // io_uring may be unavailable even on Linux, in which case the file is read normally
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        let uring = if options.io_uring && map.is_none() && file.metadata()?.is_file() {
+            uring::UringReader::new(&file, options.buffer_size).ok()
+        } else {
+            None
+        };
+
+        Ok(OpenedFile {
+            #[cfg(all(target_os = "linux", feature = "io-uring"))]
+            uring,
+            file,
+            map,
+            buffer_size: options.buffer_size,
+            drop_cache: options.drop_cache,
+        })
+    }
+
+    /// Returns the contents of the file, to be hashed.
+    pub fn input(&mut self) -> Input<'_> {
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        if let Some(uring) = &mut self.uring {
+            return Input::Reader {
+                reader: uring,
+                buffer_size: self.buffer_size,
+            };
+        }
+
+        match &self.map {
+            Some(map) => Input::Bytes(map),
+            None => Input::Reader {
+                reader: &mut self.file,
+                buffer_size: self.buffer_size,
+            },
+        }
+    }
+}
+
+impl Drop for OpenedFile {
+    fn drop(&mut self) {
+        // pages that are still mapped can't be evicted
+        self.map.take();
+        // and reads still pending in io_uring would bring pages back in
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        self.uring.take();
+
+        #[cfg(target_os = "linux")]
+        if self.drop_cache {
+            fadvise(&self.file, libc::POSIX_FADV_DONTNEED);
+        }
+    }
+}
+
+/// Gives the kernel a hint about how the whole file is going to be accessed. Errors are ignored,
+/// as the file is read the same way either way.
+#[cfg(target_os = "linux")]
+fn fadvise(file: &File, advice: libc::c_int) {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: the file descriptor stays valid for as long as `file` is borrowed
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, advice);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, io::Read};
+
+    #[test]
+    fn test_mmap_falls_back_for_empty_files() {
+        let dir = env::temp_dir().join(format!("steadyhash-input-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let full = dir.join("full");
+        let empty = dir.join("empty");
+        fs::write(&full, b"hello").unwrap();
+        fs::write(&empty, b"").unwrap();
+
+        let options = InputOptions {
+            mmap: true,
+            drop_cache: true,
+            ..Default::default()
+        };
+
+        let mut opened = OpenedFile::open(&full, &options).unwrap();
+        assert!(opened.map.is_some());
+        let mut contents = Vec::new();
+        opened.input().read_to_end(&mut contents).unwrap();
+        assert_eq!(contents, b"hello");
+
+        let opened = OpenedFile::open(&empty, &options).unwrap();
+        assert!(opened.map.is_none());
+
+        let opened = OpenedFile::open(&full, &InputOptions::default()).unwrap();
+        assert!(opened.map.is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    #[test]
+    fn test_io_uring_skips_special_files() {
+        let options = InputOptions {
+            io_uring: true,
+            ..Default::default()
+        };
+
+        let opened = OpenedFile::open(Path::new("/dev/null"), &options).unwrap();
+        assert!(opened.uring.is_none());
+    }
+}
@@ -0,0 +1,255 @@
+//! Reading files through io_uring, so that the next chunks are read by the kernel while the
+//! current one is being hashed.
+
+use io_uring::{IoUring, opcode, types};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read},
+    mem,
+    os::fd::{AsRawFd, RawFd},
+};
+
+/// Number of reads kept in flight at once.
+const QUEUE_DEPTH: u32 = 4;
+
+/// A buffer that the kernel reads a chunk of the file into.
+struct Slot {
+    buf: Vec<u8>,
+
+    /// Offset in the file of the chunk being read into the buffer
+    offset: u64,
+
+    /// Result of the read, once it's completed: the number of bytes read, or a negated errno
+    result: Option<i32>,
+}
+
+/// A sequential reader over a regular file, which keeps several reads queued ahead of the one
+/// being consumed.
+pub struct UringReader {
+    ring: IoUring,
+    fd: RawFd,
+    slots: Vec<Slot>,
+
+    /// Slots with a pending or completed read that hasn't been consumed yet, in file order
+    queue: VecDeque<usize>,
+
+    /// Offset of the next chunk to queue
+    next_offset: u64,
+
+    /// Slot being consumed, with the position in its buffer and the number of bytes read into it
+    current: Option<(usize, usize, usize)>,
+
+    /// Number of reads submitted to the kernel that haven't been reaped yet
+    in_flight: usize,
+    eof: bool,
+}
+
+impl UringReader {
+    /// Sets up a ring reading `file` in chunks of `buffer_size` bytes. Fails if io_uring isn't
+    /// available, e.g. on older kernels or when it's disabled by a seccomp filter.
+    ///
+    /// The reader only stores the file descriptor, so it must be dropped before `file`.
+    pub fn new(file: &File, buffer_size: usize) -> io::Result<UringReader> {
+        let ring = IoUring::new(QUEUE_DEPTH)?;
+        // reads are limited to u32::MAX bytes
+        let chunk_size = buffer_size.min(u32::MAX as usize);
+
+        let mut reader = UringReader {
+            ring,
+            fd: file.as_raw_fd(),
+            slots: (0..QUEUE_DEPTH)
+                .map(|_| Slot {
+                    buf: vec![0; chunk_size],
+                    offset: 0,
+                    result: None,
+                })
+                .collect(),
+            queue: VecDeque::new(),
+            next_offset: 0,
+            current: None,
+            in_flight: 0,
+            eof: false,
+        };
+
+        for slot in 0..reader.slots.len() {
+            reader.submit(slot)?;
+        }
+
+        Ok(reader)
+    }
+
+    /// Queues a read of the next chunk of the file into `slot`.
+    fn submit(&mut self, slot: usize) -> io::Result<()> {
+        let entry = &mut self.slots[slot];
+        entry.offset = self.next_offset;
+        entry.result = None;
+        self.next_offset += entry.buf.len() as u64;
+
+        let read = opcode::Read::new(
+            types::Fd(self.fd),
+            entry.buf.as_mut_ptr(),
+            entry.buf.len() as u32,
+        )
+        .offset(entry.offset)
+        .build()
+        .user_data(slot as u64);
+
+        // SAFETY: the buffer isn't touched, moved or freed until the read has been reaped, as
+        // slots are only reused after `wait` and dropping the reader waits for pending reads.
+        // The file descriptor outlives the reader.
+        unsafe { self.ring.submission().push(&read) }
+            .map_err(|_| io::Error::other("io_uring submission queue is full"))?;
+        self.ring.submit()?;
+
+        self.queue.push_back(slot);
+        self.in_flight += 1;
+        Ok(())
+    }
+
+    /// Waits until the read into `slot` has completed.
+    fn wait(&mut self, slot: usize) -> io::Result<()> {
+        while self.slots[slot].result.is_none() {
+            match self.ring.submit_and_wait(1) {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+
+            for completion in self.ring.completion() {
+                self.slots[completion.user_data() as usize].result = Some(completion.result());
+                self.in_flight -= 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Waits for every queued read and forgets about them.
+    fn discard_queue(&mut self) -> io::Result<()> {
+        while let Some(slot) = self.queue.pop_front() {
+            self.wait(slot)?;
+        }
+
+        Ok(())
+    }
+
+    /// Makes the next chunk of the file current. Returns `false` at the end of the file.
+    fn advance(&mut self) -> io::Result<bool> {
+        if let Some((slot, ..)) = self.current.take() {
+            self.submit(slot)?;
+        }
+
+        loop {
+            let Some(slot) = self.queue.pop_front() else {
+                return Ok(false);
+            };
+            self.wait(slot)?;
+
+            let result = self.slots[slot].result.unwrap_or_default();
+            if result == -libc::EINTR || result == -libc::EAGAIN {
+                // retry the same chunk once everything queued after it has been thrown away
+                let offset = self.slots[slot].offset;
+                self.discard_queue()?;
+                self.next_offset = offset;
+                for slot in 0..self.slots.len() {
+                    self.submit(slot)?;
+                }
+                continue;
+            }
+            if result < 0 {
+                return Err(io::Error::from_raw_os_error(-result));
+            }
+
+            let len = result as usize;
+            if len == 0 {
+                self.eof = true;
+                return Ok(false);
+            }
+
+            if len < self.slots[slot].buf.len() {
+                // the reads queued after a short one started at the wrong offset, so they're
+                // redone from where this one stopped
+                self.discard_queue()?;
+                self.next_offset = self.slots[slot].offset + len as u64;
+                for other in (0..self.slots.len()).filter(|&other| other != slot) {
+                    self.submit(other)?;
+                }
+            }
+
+            self.current = Some((slot, 0, len));
+            return Ok(true);
+        }
+    }
+}
+
+impl Read for UringReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if let Some((slot, pos, len)) = &mut self.current
+                && *pos < *len
+            {
+                let count = buf.len().min(*len - *pos);
+                buf[..count].copy_from_slice(&self.slots[*slot].buf[*pos..*pos + count]);
+                *pos += count;
+                return Ok(count);
+            }
+
+            if self.eof || !self.advance()? {
+                return Ok(0);
+            }
+        }
+    }
+}
+
+impl Drop for UringReader {
+    fn drop(&mut self) {
+        while self.in_flight > 0 {
+            match self.ring.submit_and_wait(self.in_flight) {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => {
+                    // the kernel may still write into the buffers, so they're leaked rather than
+                    // freed
+                    mem::forget(mem::take(&mut self.slots));
+                    return;
+                }
+            }
+
+            self.in_flight -= self.ring.completion().count();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn test_reads_whole_file() {
+        let path = env::temp_dir().join(format!("steadyhash-uring-{}", std::process::id()));
+        // several rounds of the queue, ending with a partial chunk
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 7 % 251) as u8).collect();
+        fs::write(&path, &data).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let mut reader = match UringReader::new(&file, 512) {
+            Ok(reader) => reader,
+            // io_uring isn't available everywhere, e.g. in some containers
+            Err(_) => return fs::remove_file(&path).unwrap(),
+        };
+
+        let mut contents = Vec::new();
+        reader.read_to_end(&mut contents).unwrap();
+        assert_eq!(contents, data);
+        assert_eq!(reader.read(&mut [0; 16]).unwrap(), 0);
+
+        drop(reader);
+        fs::remove_file(&path).unwrap();
+    }
+}
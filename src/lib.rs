@@ -0,0 +1,28 @@
+//! The checksum algorithms and manifest handling behind the `steadyhash` command-line tool.
+//!
+//! ```
+//! use steadyhash::{Checksum, HashOptions, hashing::Input};
+//!
+//! let options = HashOptions {
+//!     bit_length: 256,
+//!     ..Default::default()
+//! };
+//! let checksum = Checksum::Sha.calculate(&options, Input::Bytes(b"abc")).unwrap();
+//! assert_eq!(
+//!     checksum,
+//!     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+//! );
+//! ```
+
+mod checksum;
+pub mod encoding;
+pub mod errors;
+#[cfg(feature = "steadyhash-ffi")]
+pub mod ffi;
+pub mod hashing;
+pub mod manifest;
+pub mod registry;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use checksum::{Checksum, ChecksumBuilder, HashOptions, calculate_all, hash_reader};
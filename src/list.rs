@@ -0,0 +1,93 @@
+//! The `list` subcommand and `--list-algorithms`, which describe every supported algorithm.
+
+use crate::json;
+use std::fmt::Write;
+use steadyhash::registry::{Algorithm, Registry};
+
+/// Formats the algorithms of `registry`, as a table or as a JSON array.
+pub fn render(registry: &Registry, as_json: bool) -> String {
+    let algorithms = registry.algorithms();
+
+    if as_json {
+        let entries: Vec<String> = algorithms.iter().map(json_entry).collect();
+        return format!("[{}]\n", entries.join(","));
+    }
+
+    let mut table = format!(
+        "{:<14} {:<26} {:<8} {}\n",
+        "NAME", "BIT LENGTHS", "DEFAULT", "ALIASES"
+    );
+    for algorithm in algorithms {
+        let row = format!(
+            "{:<14} {:<26} {:<8} {}",
+            algorithm.name(),
+            format_bit_lengths(&algorithm.bit_lengths),
+            algorithm.default_bits,
+            algorithm.names[1..].join(", "),
+        );
+        let _ = writeln!(table, "{}", row.trim_end());
+    }
+    table
+}
+
+fn json_entry(algorithm: &Algorithm) -> String {
+    let aliases: Vec<String> = algorithm.names[1..]
+        .iter()
+        .map(|name| json::string(name))
+        .collect();
+    let bit_lengths: Vec<String> = algorithm
+        .bit_lengths
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    format!(
+        r#"{{"name":{},"aliases":[{}],"bit_lengths":[{}],"any_bit_length":{},"default_bits":{}}}"#,
+        json::string(algorithm.name()),
+        aliases.join(","),
+        bit_lengths.join(","),
+        algorithm.bit_lengths.is_empty(),
+        algorithm.default_bits,
+    )
+}
+
+/// Formats a list of bit lengths, collapsing long runs of multiples of 8.
+fn format_bit_lengths(bit_lengths: &[usize]) -> String {
+    match bit_lengths {
+        [] => "any multiple of 8".into(),
+        [first, .., last]
+            if bit_lengths.len() > 8 && bit_lengths.windows(2).all(|w| w[1] - w[0] == 8) =>
+        {
+            format!("{first}-{last}, multiples of 8")
+        }
+        _ => bit_lengths
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bit_lengths() {
+        assert_eq!(format_bit_lengths(&[]), "any multiple of 8");
+        assert_eq!(format_bit_lengths(&[256, 512]), "256, 512");
+        let blake2b: Vec<usize> = (1..=64).map(|i| i * 8).collect();
+        assert_eq!(format_bit_lengths(&blake2b), "8-512, multiples of 8");
+    }
+
+    #[test]
+    fn test_json() {
+        let listing = render(&Registry::builtin(), true);
+        assert!(listing.starts_with(
+            r#"[{"name":"sha","aliases":[],"bit_lengths":[160,224,256,384,512],"any_bit_length":false,"default_bits":256}"#
+        ));
+        assert!(listing.contains(
+            r#"{"name":"blake3","aliases":["b3"],"bit_lengths":[],"any_bit_length":true"#
+        ));
+    }
+}
@@ -1,234 +1,2337 @@
-use anyhow::Error;
-use clap::Parser;
+use anyhow::{Context, Error};
+use clap::{
+    CommandFactory, Parser, Subcommand,
+    builder::{PossibleValuesParser, TypedValueParser},
+    error::ErrorKind,
+};
+use clap_complete::Shell;
 use std::{
-    fmt::Display,
-    fs::File,
-    io::{self, BufReader, Read},
+    borrow::Cow,
+    collections::HashMap,
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, IsTerminal, Read, Write},
     path::{Path, PathBuf},
-    str::FromStr,
+    process::ExitCode,
+    slice,
+    time::{Duration, Instant},
+};
+
+mod bench;
+mod checkpoint;
+mod color;
+mod completions;
+mod config;
+mod diff;
+mod files;
+mod input;
+mod json;
+mod list;
+mod manpage;
+mod multicall;
+mod output;
+mod parallel;
+mod priority;
+mod progress;
+mod report;
+mod signature;
+mod stats;
+mod text;
+mod throttle;
+mod tree;
+mod units;
+mod update;
+use crate::{
+    checkpoint::{Checkpoint, Resumed},
+    color::ColorMode,
+    completions::ChecksumTypeParser,
+    config::Defaults,
+    files::{Pattern, WalkOptions},
+    input::{InputOptions, OpenedFile},
+    output::{AtomicFile, Destination, OutputFormat, Printer, Record, Template},
+    parallel::{for_each_ordered, resolve_jobs},
+    progress::{CountingReader, ProgressBar, ProgressMode},
+    report::{Report, ReportEntry},
+    stats::{Stats, StatsFormat},
+    text::TextReader,
+    throttle::{RateLimiter, ThrottledReader},
+    units::{parse_rate, parse_size},
+};
+use steadyhash::{
+    Checksum, HashOptions, calculate_all,
+    encoding::{self, Encoding},
+    errors::{
+        CheckpointError, EncodingError, ExternalHasherError, HashOptionsError, ManifestError,
+        Sp800185SumError,
+    },
+    hashing::{Input, external::ExternalHasher},
+    manifest::{self, Clearsign, DigestFormat, Entry, Status, Summary, Verification},
+    registry::Registry,
 };
 
-mod errors;
-mod hashing;
-use crate::{errors::ParseChecksumError, hashing::Hasher};
-use hashing::{blake2b::Blake2b, md5::Md5Sum, sha3::Sha3Sum, shasum::ShaSum};
+/// Algorithm used when neither `--type` nor the configuration set one, which is SHA-256 at the
+/// default bit length.
+const DEFAULT_TYPE: &str = "sha";
 
 #[derive(Parser)]
 #[clap(
     version,
-    about = "Pure Rust utility which handles various checksum types"
+    about = "Pure Rust utility which handles various checksum types",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
 )]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    // without a subcommand, the options of `hash` are accepted along with --check, as in earlier
+    // versions
+    #[clap(flatten)]
+    hash: HashArgs,
+
+    #[clap(short, long, help = "read checksums from the FILEs and check them")]
+    check: bool,
+
+    #[clap(flatten)]
+    verify: VerifyArgs,
+
+    #[clap(
+        long,
+        conflicts_with_all = ["check", "FILEs"],
+        help = "list the supported algorithms, with their bit lengths and aliases"
+    )]
+    list_algorithms: bool,
+
+    #[clap(
+        long,
+        requires = "list_algorithms",
+        help = "list the algorithms as JSON"
+    )]
+    json: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compute the checksums of files
+    Hash(HashArgs),
+
+    /// Read checksums from manifests and check them
+    Check(CheckArgs),
+
+    /// Measure the throughput of every algorithm on this machine
+    Bench {
+        #[clap(help = "hash this file instead of an in-memory buffer")]
+        file: Option<PathBuf>,
+
+        #[clap(
+            long,
+            value_name = "SIZE",
+            value_parser = parse_size,
+            default_value = "16MiB",
+            help = "the size of the in-memory buffer"
+        )]
+        size: u64,
+
+        #[clap(
+            short,
+            long,
+            value_name = "N",
+            default_value_t = 0,
+            help = "the number of threads for algorithms that can use several (0 for one per CPU)"
+        )]
+        jobs: usize,
+    },
+
+    /// Compare two manifests, listing the files added, removed and changed between them
+    Diff {
+        #[clap(help = "the earlier manifest")]
+        old: PathBuf,
+
+        #[clap(help = "the later manifest")]
+        new: PathBuf,
+
+        #[clap(
+            short = 't',
+            long = "type",
+            value_parser = ChecksumTypeParser,
+            hide_possible_values = true,
+            help = "the type of the checksums, which only matters for multi-field ones like cksum's [default: sha]"
+        )]
+        checksum_type: Option<String>,
+
+        #[clap(
+            long,
+            help = "report removed and added files with the same checksum as renamed"
+        )]
+        renames: bool,
+    },
+
+    /// Compute a single checksum for a whole directory, covering the paths and contents of its files
+    TreeHash {
+        #[clap(help = "the directory to hash")]
+        dir: PathBuf,
+
+        #[clap(
+            short = 't',
+            long = "type",
+            value_parser = ChecksumTypeParser,
+            hide_possible_values = true,
+            help = "the algorithm hashing the files and their listing [default: sha]"
+        )]
+        checksum_type: Option<String>,
+
+        #[clap(long, help = "cover the permissions of the files too")]
+        permissions: bool,
+
+        #[clap(
+            long,
+            help = "cover the symlinks by their target, instead of skipping them"
+        )]
+        symlinks: bool,
+
+        #[clap(
+            long,
+            value_name = "GLOB",
+            help = "skip the files and directories matching GLOB, like 'target/' or '*.o'; can be given several times"
+        )]
+        exclude: Vec<Pattern>,
+    },
+
+    /// List the supported algorithms, with their bit lengths and aliases
+    List {
+        #[clap(long, help = "print the list as JSON")]
+        json: bool,
+    },
+
+    /// Print a completion script for a shell
+    Completions {
+        #[clap(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print the man page, in roff format
+    Manpage,
+}
+
+#[derive(clap::Args)]
+struct HashArgs {
+    #[clap(flatten)]
+    algorithm: AlgorithmArgs,
+
+    #[clap(flatten)]
+    input: InputArgs,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+
+    #[clap(name = "FILEs", help = "the files to process, '-' being stdin")]
+    file_path: Vec<PathBuf>,
+
+    #[clap(
+        long = "bsd",
+        visible_alias = "tag",
+        help = "create a BSD-style checksum, as coreutils' --tag does"
+    )]
+    bsd: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "encoding",
+        help = "print the checksums in uppercase hex"
+    )]
+    uppercase: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "text",
+        help = "read in binary mode, marking the files with '*' (the default except on Windows)"
+    )]
+    binary: bool,
+
+    #[clap(
+        long,
+        conflicts_with_all = ["bsd", "uppercase", "encoding", "checkpoint"],
+        help = "write the checksum of a single input as raw bytes, without encoding it"
+    )]
+    raw: bool,
+
+    #[clap(
+        long,
+        conflicts_with_all = ["uppercase", "raw"],
+        help = "write the checksums as multihashes, in the multibase matching --encoding"
+    )]
+    multihash: bool,
+
+    #[clap(
+        long,
+        value_name = "TEMPLATE",
+        conflicts_with_all = ["bsd", "raw"],
+        help = "print each checksum with a template like '{hash}  {file}', with the placeholders {hash}, {HASH}, {file}, {size}, {algo} and {mtime}"
+    )]
+    format: Option<Template>,
+
+    #[clap(
+        short,
+        long,
+        conflicts_with_all = ["checkpoint", "FILEs"],
+        help = "read data from stdin, which is also read without FILEs if it isn't a terminal"
+    )]
+    stdin: bool,
+
+    #[clap(
+        short,
+        long,
+        conflicts_with = "raw",
+        help = "end each output line with NUL instead of a newline, or with --check, read manifests of NUL-terminated lines"
+    )]
+    zero: bool,
+
+    #[clap(
+        short = 'o',
+        long = "output",
+        value_name = "FILE",
+        conflicts_with = "raw",
+        help = "write the checksums to FILE, which is replaced once they're all written; with several --type, a FILE containing {algo}, like 'SUMS.{algo}', gets a file per algorithm"
+    )]
+    output_path: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "MANIFEST",
+        conflicts_with_all = ["raw", "stdin", "format", "zero", "output_path", "checkpoint"],
+        help = "update MANIFEST in place, only hashing the FILEs and listed files which are new or were modified since it was written, and dropping deleted files"
+    )]
+    update: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "resolve the relative paths of the manifests against DIR instead of their directories (check mode and --update only)"
+    )]
+    base_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "DIGEST",
+        conflicts_with_all = ["raw", "stdin", "format", "output_path", "update", "checkpoint", "bsd", "multihash"],
+        help = "compare the checksum of each FILE with a DIGEST, given once per FILE in the same order, printing OK or FAILED"
+    )]
+    expect: Vec<String>,
+
+    #[clap(
+        long,
+        value_name = "TEXT",
+        conflicts_with_all = ["raw", "stdin", "format", "update", "checkpoint", "expect"],
+        help = "hash TEXT itself rather than a file, printing lines like 'HASH \"TEXT\"'; can be given several times"
+    )]
+    string: Vec<String>,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with = "stdin",
+        help = "hash the files listed in FILE, one per line, after the FILEs; '-' reads the list from stdin"
+    )]
+    files_from: Option<PathBuf>,
+
+    #[clap(
+        short = '0',
+        long,
+        requires = "files_from",
+        help = "read the list of --files-from as NUL-terminated paths, as written by find -print0"
+    )]
+    null: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "hash every regular file under the directories among the FILEs, without following symlinks"
+    )]
+    recursive: bool,
+
+    #[clap(
+        long,
+        value_name = "GLOB",
+        requires = "recursive",
+        help = "with --recursive, only hash the files matching GLOB, like '*.iso'; can be given several times"
+    )]
+    include: Vec<Pattern>,
+
+    #[clap(
+        long,
+        value_name = "GLOB",
+        requires = "recursive",
+        help = "with --recursive, skip the files and directories matching GLOB, like 'target/' or '*.o'; can be given several times"
+    )]
+    exclude: Vec<Pattern>,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        requires = "recursive",
+        help = "with --recursive, skip the files and directories matching the patterns in FILE, one per line"
+    )]
+    exclude_from: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "N",
+        requires = "recursive",
+        help = "with --recursive, only hash the files up to N levels below the directories, 1 being the files directly in them"
+    )]
+    max_depth: Option<usize>,
+
+    #[clap(
+        long,
+        overrides_with = "no_hidden",
+        requires = "recursive",
+        help = "with --recursive, hash the files and directories whose name starts with a '.' (the default)"
+    )]
+    hidden: bool,
+
+    #[clap(
+        long,
+        overrides_with = "hidden",
+        requires = "recursive",
+        help = "with --recursive, skip the files and directories whose name starts with a '.'"
+    )]
+    no_hidden: bool,
+
+    #[clap(
+        long,
+        overrides_with = "no_follow",
+        requires = "recursive",
+        help = "with --recursive, follow the symlinks found in the directories, skipping the ones that loop"
+    )]
+    follow_symlinks: bool,
+
+    #[clap(
+        long,
+        overrides_with = "follow_symlinks",
+        requires = "recursive",
+        help = "with --recursive, skip the symlinks found in the directories (the default)"
+    )]
+    no_follow: bool,
+
+    #[clap(
+        long,
+        help = "hash the files in the order of their paths, written with '/' separators on every platform, so that manifests are reproducible"
+    )]
+    sort: bool,
+}
+
+#[derive(clap::Args)]
+struct CheckArgs {
+    #[clap(flatten)]
+    algorithm: AlgorithmArgs,
+
+    #[clap(flatten)]
+    input: InputArgs,
+
+    #[clap(flatten)]
+    output: OutputArgs,
+
+    #[clap(flatten)]
+    verify: VerifyArgs,
+
+    #[clap(
+        short,
+        long,
+        help = "read manifests of NUL-terminated lines, as written by hash --zero"
+    )]
+    zero: bool,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "resolve the relative paths of the manifests against DIR instead of their directories"
+    )]
+    base_dir: Option<PathBuf>,
+
+    #[clap(
+        name = "FILEs",
+        help = "the manifests listing the checksums to check, read from stdin if there's none or for '-'"
+    )]
+    file_path: Vec<PathBuf>,
+}
+
+/// Options that only apply to check mode.
+#[derive(clap::Args, Default, PartialEq)]
+struct VerifyArgs {
+    #[clap(
+        short,
+        long,
+        help = "don't print the files that are OK (check mode only)"
+    )]
+    quiet: bool,
+
+    #[clap(
+        long,
+        help = "don't print anything, the exit status tells whether every file is OK (check mode only)"
+    )]
+    status: bool,
+
+    #[clap(
+        long,
+        help = "fail if a line of a manifest is improperly formatted (check mode only)"
+    )]
+    strict: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "warn about improperly formatted lines of the manifests (check mode only)"
+    )]
+    warn: bool,
+
+    #[clap(
+        long,
+        help = "don't fail or report the status of missing files (check mode only)"
+    )]
+    ignore_missing: bool,
+
+    /// `--base-dir`, which is an option of `hash` too for `--update`
+    #[clap(skip)]
+    base_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "write a JSON report of the result of every line of the manifests to FILE (check mode only)"
+    )]
+    report_file: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "SIGFILE",
+        requires = "pubkey",
+        help = "verify the minisign or signify signature SIGFILE of the manifest before checking it (check mode only)"
+    )]
+    verify_sig: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "KEY",
+        requires = "verify_sig",
+        help = "the public key file, or base64 public key, verifying the signature of --verify-sig"
+    )]
+    pubkey: Option<String>,
+
+    /// Whether the lines of the manifests end with NUL, from `-z, --zero`, which `hash` shares
+    #[clap(skip)]
+    zero: bool,
+}
+
+/// Options selecting the algorithm and its parameters.
+#[derive(clap::Args)]
+struct AlgorithmArgs {
     #[clap(
         short = 'l',
         long = "length",
-        help = "the bit length of the checksum",
-        required_if_eq("checksum_type", "sha"),
-        required_if_eq("checksum_type", "sha3"),
-        required_if_eq("checksum_type", "blake2b")
+        help = "the bit length of the checksum, unless it's part of the type (e.g. sha3-512)"
     )]
     bit_length: Option<usize>,
 
     #[clap(
         short = 't',
         long = "type",
-        help = "the type of checksum (sha or blake)"
+        value_parser = ChecksumTypeParser,
+        hide_possible_values = true,
+        help = "the type of checksum (sha256, sha3-512, blake2b, md5, crc32, ...), which can be repeated to compute several checksums in one pass; tuplehash keeps its whole input in memory, so it only hashes inputs of up to 1 GiB [default: sha, i.e. SHA-256, unless configured otherwise]"
     )]
-    checksum_type: String,
+    checksum_type: Vec<String>,
 
-    #[clap(name = "FILEs", help = "the files to process")]
-    file_path: Vec<PathBuf>,
+    #[clap(
+        long,
+        value_name = "HEX",
+        help = "the hex-encoded key for keyed algorithms"
+    )]
+    key: Option<String>,
 
-    #[clap(short, long, help = "read checksums from the FILEs and check them")]
-    check: bool,
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with = "key",
+        help = "read the raw key for keyed algorithms from FILE"
+    )]
+    key_file: Option<PathBuf>,
 
-    #[clap(long = "bsd", help = "create a BSD-style checksum")]
-    bsd: bool,
+    #[clap(
+        long,
+        value_name = "VAR",
+        conflicts_with_all = ["key", "key_file"],
+        help = "read the hex-encoded key from the environment variable VAR"
+    )]
+    key_env: Option<String>,
 
-    #[clap(long, help = "read in binary mode")]
-    binary: bool,
+    #[clap(long, help = "compute an HMAC of the data, using the given key")]
+    hmac: bool,
 
-    #[clap(short, long, help = "read data from stdin")]
-    stdin: bool,
+    #[clap(long, value_name = "HEX", help = "the hex-encoded salt (blake2b)")]
+    salt: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "HEX",
+        visible_alias = "personalization",
+        help = "the hex-encoded personalization string (blake2b)"
+    )]
+    personal: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "CONTEXT",
+        conflicts_with_all = ["key", "key_file", "key_env", "hmac"],
+        help = "derive a key with the given context string (blake3)"
+    )]
+    derive_key: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "STRING",
+        help = "the customization string (cshake, kmac, tuplehash)"
+    )]
+    customization: Option<String>,
+
+    #[clap(long, help = "the seed for seeded algorithms (murmur3)")]
+    seed: Option<u32>,
+
+    #[clap(
+        long,
+        value_name = "NAME=COMMAND",
+        help = "register an algorithm computed by COMMAND, which reads the data on stdin and prints the checksum"
+    )]
+    external_hasher: Vec<ExternalHasher>,
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-enum Checksum {
-    Sha,
-    Sha3,
-    Md5,
-    Blake2b,
+/// Options controlling how files are read.
+#[derive(clap::Args)]
+struct InputArgs {
+    #[clap(long, help = "memory-map regular files instead of reading them")]
+    mmap: bool,
+
+    #[clap(
+        long,
+        value_name = "SIZE",
+        value_parser = parse_size,
+        help = "the size of the read buffer, e.g. 64K or 4MiB [default: 64K]"
+    )]
+    buffer_size: Option<u64>,
+
+    #[clap(
+        long,
+        help = "evict files from the page cache after hashing them (Linux only)"
+    )]
+    drop_cache: bool,
+
+    #[clap(
+        long,
+        value_name = "RATE",
+        value_parser = parse_rate,
+        help = "limit the combined read rate of all files, e.g. 50MiB/s"
+    )]
+    limit_rate: Option<u64>,
+
+    #[clap(
+        long,
+        help = "only read from the disk when it's otherwise idle, to run in the background"
+    )]
+    nice_io: bool,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "record the processed files in FILE, so that an interrupted run can be resumed"
+    )]
+    checkpoint: Option<PathBuf>,
+
+    #[clap(
+        long,
+        requires = "checkpoint",
+        help = "skip the files recorded in the checkpoint by an interrupted run"
+    )]
+    resume: bool,
+
+    #[clap(
+        long,
+        value_name = "WHEN",
+        value_enum,
+        default_value_t = ProgressMode::Auto,
+        help = "when to show progress on stderr (auto: for large inputs on a terminal)"
+    )]
+    progress: ProgressMode,
+
+    #[clap(
+        long,
+        help = "read in text mode, with CRLF line endings read as LF (the default on Windows)"
+    )]
+    text: bool,
+
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text",
+        help = "print throughput statistics to stderr at the end of the run; several --type are reported together, as they share a single read of each file"
+    )]
+    stats: Option<StatsFormat>,
+
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    #[clap(
+        long,
+        conflicts_with = "mmap",
+        help = "read regular files through io_uring, overlapping reads with hashing"
+    )]
+    io_uring: bool,
+
+    #[clap(
+        short,
+        long,
+        value_name = "N",
+        help = "hash up to N files at once, and use N threads per file with blake3 (0 for one per CPU) [default: 1]"
+    )]
+    jobs: Option<usize>,
+}
+
+/// Options controlling how the results are printed.
+#[derive(clap::Args)]
+struct OutputArgs {
+    #[clap(
+        long,
+        value_name = "WHEN",
+        value_enum,
+        help = "when to color the results of check mode (auto: when stdout is a terminal) [default: auto]"
+    )]
+    color: Option<ColorMode>,
+
+    #[clap(
+        long,
+        value_name = "ENCODING",
+        value_parser = PossibleValuesParser::new(Encoding::ALL.iter().map(|encoding| encoding.name()))
+            .map(|name| name.parse::<Encoding>().expect("only valid names are accepted")),
+        default_value = "hex",
+        help = "how checksums are written, in the output or in the manifests being checked"
+    )]
+    encoding: Encoding,
+
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "how the checksums or the results of check mode are printed"
+    )]
+    output_format: OutputFormat,
+}
+
+impl HashArgs {
+    /// Fills in the options that weren't given with `defaults`.
+    fn apply_defaults(&mut self, defaults: &Defaults) {
+        self.algorithm.apply_defaults(defaults);
+        self.input.apply_defaults(defaults);
+        self.output.apply_defaults(defaults);
+        self.bsd |= defaults.bsd.unwrap_or_default();
+    }
 }
 
-impl Checksum {
-    const fn default_bits(self) -> usize {
-        match self {
-            Checksum::Md5 => 128,
-            // sensible default
-            _ => 256,
+impl AlgorithmArgs {
+    /// Fills in the options that weren't given with `defaults`.
+    fn apply_defaults(&mut self, defaults: &Defaults) {
+        // the default length only applies to the default type
+        if self.checksum_type.is_empty() {
+            self.checksum_type.extend(defaults.checksum_type.clone());
+            self.bit_length = self.bit_length.or(defaults.bit_length);
         }
     }
+}
+
+impl OutputArgs {
+    /// Fills in the options that weren't given with `defaults`.
+    fn apply_defaults(&mut self, defaults: &Defaults) {
+        self.color = self.color.or(defaults.color);
+    }
+}
 
-    /// Hashes some data, given a bit length.
-    fn calculate(&self, bit_length: usize, data: impl AsRef<[u8]>) -> Result<String, Error> {
-        let data = data.as_ref();
-        Ok(match self {
-            Checksum::Sha => ShaSum::new(bit_length, data)?.get_checksum(),
-            Checksum::Blake2b => Blake2b::new(bit_length, data)?.get_checksum(),
-            // bit length gets ignored
-            Checksum::Md5 => Md5Sum::new(data).get_checksum(),
-            Checksum::Sha3 => Sha3Sum::new(bit_length, data)?.get_checksum(),
-        })
+impl InputArgs {
+    /// Fills in the options that weren't given with `defaults`.
+    fn apply_defaults(&mut self, defaults: &Defaults) {
+        self.jobs = self.jobs.or(defaults.jobs);
     }
 
-    // Prints the checksum in an user-friendly way.
-    fn print(&self, bit_length: usize, file: &Path, checksum_str: impl AsRef<str>, bsd: bool) {
-        let name = match self {
-            Checksum::Sha => if bit_length == 160 { "SHA1" } else { &format!("SHA{}", bit_length) },
-            Checksum::Sha3 => &format!("SHA3-{}", bit_length),
-            Checksum::Blake2b => &format!("BLAKE2b-{}", bit_length),
-            Checksum::Md5 => "MD5",
-        };
+    /// Number of files to hash at once, as given to `--jobs`.
+    fn jobs(&self) -> usize {
+        self.jobs.unwrap_or(1)
+    }
+}
 
-        let checksum_str = checksum_str.as_ref();
+/// What a run does with the files it's given.
+#[derive(Clone, Copy)]
+enum Mode<'a> {
+    /// Compute the checksums of the files
+    Hash(&'a HashArgs),
 
-        if bsd {
-            println!("{name} ({}) = {checksum_str}", file.display());
+    /// Check the checksums listed in the manifests
+    Check(&'a [PathBuf], &'a VerifyArgs),
+
+    /// Check the checksums of the files against the digests given to `--expect`
+    Expect(&'a HashArgs),
+}
+
+impl<'a> Mode<'a> {
+    /// The mode of a run given the options of hash mode, which checks the files with `--expect`.
+    fn for_hash(args: &'a HashArgs) -> Mode<'a> {
+        if args.expect.is_empty() {
+            Mode::Hash(args)
         } else {
-            println!("{checksum_str}  {}", file.display());
+            Mode::Expect(args)
         }
     }
+
+    /// Whether the run prints the results of checks rather than checksums.
+    fn checks(self) -> bool {
+        matches!(self, Mode::Check(..) | Mode::Expect(_))
+    }
 }
 
-impl Display for Checksum {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Checksum::Blake2b => write!(f, "BLAKE2b"),
-            Checksum::Md5 => write!(f, "MD5"),
-            Checksum::Sha => write!(f, "SHA"),
-            Checksum::Sha3 => write!(f, "SHA3"),
+fn main() -> Result<ExitCode, Error> {
+    let mut args = match env::args_os()
+        .next()
+        .as_deref()
+        .and_then(multicall::invoked_as)
+    {
+        // the coreutils tools aren't configurable
+        Some(program) => multicall::CompatArgs::parse().into_args(program),
+        None => {
+            let mut args = Args::parse();
+            let defaults = Defaults::load()?;
+            match &mut args.command {
+                Some(Command::Hash(hash)) => hash.apply_defaults(&defaults),
+                Some(Command::Check(check)) => {
+                    check.algorithm.apply_defaults(&defaults);
+                    check.input.apply_defaults(&defaults);
+                    check.output.apply_defaults(&defaults);
+                }
+                Some(_) => {}
+                None => args.hash.apply_defaults(&defaults),
+            }
+            args
+        }
+    };
+
+    // `-z` and `--base-dir` are options of `hash` too, so check mode gets them from wherever they
+    // were given
+    match &mut args.command {
+        Some(Command::Check(check)) => {
+            check.verify.zero = check.zero;
+            check.verify.base_dir = check.base_dir.take();
+        }
+        None if args.check => {
+            args.verify.zero = args.hash.zero;
+            args.verify.base_dir = args.hash.base_dir.take();
         }
+        _ => {}
     }
+
+    let summary = match &args.command {
+        Some(Command::Hash(hash)) => run(
+            &hash.algorithm,
+            &hash.input,
+            &hash.output,
+            Mode::for_hash(hash),
+        )?,
+        Some(Command::Check(check)) => run(
+            &check.algorithm,
+            &check.input,
+            &check.output,
+            Mode::Check(&check.file_path, &check.verify),
+        )?,
+        Some(Command::Bench { file, size, jobs }) => {
+            let threads = resolve_jobs(*jobs);
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build_global()?;
+            let size = usize::try_from(*size).context("the buffer size doesn't fit in memory")?;
+
+            bench::run(size, file.as_deref(), threads, &InputOptions::default())?;
+            Summary::default()
+        }
+        Some(Command::Diff {
+            old,
+            new,
+            checksum_type,
+            renames,
+        }) => {
+            let changes =
+                Checksum::parse_with_length(checksum_type.as_deref().unwrap_or(DEFAULT_TYPE))
+                    .map_err(Error::from)
+                    .and_then(|(checksum, _)| {
+                        Ok(diff::diff(
+                            &diff::read_manifest(old, checksum)?,
+                            &diff::read_manifest(new, checksum)?,
+                            *renames,
+                        ))
+                    });
+            // like diff, fail with 1 if the manifests differ, and with 2 on errors
+            let changes = match changes {
+                Ok(changes) => changes,
+                Err(e) => {
+                    eprintln!("error: {e:#}");
+                    return Ok(ExitCode::from(2));
+                }
+            };
+            for change in &changes {
+                println!("{change}");
+            }
+            return Ok(if changes.is_empty() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            });
+        }
+        Some(Command::TreeHash {
+            dir,
+            checksum_type,
+            permissions,
+            symlinks,
+            exclude,
+        }) => {
+            let (checksum, bit_length) =
+                Checksum::parse_with_length(checksum_type.as_deref().unwrap_or(DEFAULT_TYPE))?;
+            let options = HashOptions {
+                bit_length: bit_length.unwrap_or_else(|| checksum.default_bits()),
+                ..Default::default()
+            };
+            let walk = WalkOptions {
+                exclude: exclude.clone(),
+                keep_symlinks: *symlinks,
+                ..Default::default()
+            };
+            let digest = tree::tree_hash(checksum, &options, dir, &walk, *permissions)?;
+            println!("{digest}  {}", dir.display());
+            Summary::default()
+        }
+        Some(Command::List { json }) => {
+            print!("{}", list::render(&Registry::builtin(), *json));
+            Summary::default()
+        }
+        Some(Command::Completions { shell }) => {
+            completions::print(*shell);
+            Summary::default()
+        }
+        Some(Command::Manpage) => {
+            manpage::render(&mut io::stdout().lock())?;
+            Summary::default()
+        }
+        None if args.list_algorithms => {
+            print!("{}", list::render(&Registry::builtin(), args.json));
+            Summary::default()
+        }
+        None if !args.check && args.verify != VerifyArgs::default() => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "the options of check mode only apply with --check",
+                )
+                .exit();
+        }
+        None if args.check => run(
+            &args.hash.algorithm,
+            &args.hash.input,
+            &args.hash.output,
+            Mode::Check(&args.hash.file_path, &args.verify),
+        )?,
+        None => run(
+            &args.hash.algorithm,
+            &args.hash.input,
+            &args.hash.output,
+            Mode::for_hash(&args.hash),
+        )?,
+    };
+
+    let strict = match &args.command {
+        Some(Command::Check(check)) => check.verify.strict,
+        Some(_) => false,
+        None => args.verify.strict,
+    };
+    // like sha256sum, fail if a file didn't match or couldn't be read
+    Ok(if summary.passed(strict) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
 }
 
-impl FromStr for Checksum {
-    type Err = ParseChecksumError;
+/// Hashes or checks files, with the algorithm, input and output options shared by both modes.
+/// Returns the results of check mode, or the number of files hash mode couldn't read.
+fn run(
+    algorithm: &AlgorithmArgs,
+    input: &InputArgs,
+    output: &OutputArgs,
+    mode: Mode,
+) -> Result<Summary, Error> {
+    // like coreutils, the manifest is read from stdin without FILEs
+    let stdin = [PathBuf::from("-")];
+    let mode = match mode {
+        Mode::Check([], verify) => Mode::Check(&stdin, verify),
+        mode => mode,
+    };
+    // SFV files are written with `--output-format sfv` and recognized by their extension
+    let sfv = match mode {
+        Mode::Hash(_) => output.output_format == OutputFormat::Sfv,
+        Mode::Expect(_) => false,
+        Mode::Check(manifests, _) => manifests.iter().all(|manifest| is_sfv(manifest)),
+    };
+    // without a type, check mode finds the algorithm of each entry
+    let detect = mode.checks()
+        && !sfv
+        && algorithm.checksum_type.is_empty()
+        && algorithm.bit_length.is_none();
+    let checksum_types: Vec<&str> = if algorithm.checksum_type.is_empty() {
+        vec![if sfv { "crc32" } else { DEFAULT_TYPE }]
+    } else {
+        algorithm.checksum_type.iter().map(String::as_str).collect()
+    };
+    match mode {
+        Mode::Check(..) if checksum_types.len() > 1 => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "manifests can only be checked with a single --type",
+                )
+                .exit();
+        }
+        Mode::Hash(args) if args.raw && (checksum_types.len() > 1 || args.file_path.len() > 1) => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--raw can only write the checksum of a single input with a single --type",
+                )
+                .exit();
+        }
+        Mode::Hash(args)
+            if output.output_format != OutputFormat::Text
+                && (args.bsd || args.raw || args.format.is_some()) =>
+        {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--bsd, --raw and --format only apply to the text output format",
+                )
+                .exit();
+        }
+        Mode::Hash(args)
+            if args.update.is_some()
+                && (checksum_types.len() > 1 || output.output_format != OutputFormat::Text) =>
+        {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--update only applies to manifests of a single --type in the text output format",
+                )
+                .exit();
+        }
+        Mode::Hash(args) | Mode::Expect(args)
+            if args.base_dir.is_some() && args.update.is_none() =>
+        {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--base-dir only applies to check mode and --update",
+                )
+                .exit();
+        }
+        Mode::Hash(args)
+            if !args.string.is_empty() && output.output_format != OutputFormat::Text =>
+        {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--string only applies to the text output format",
+                )
+                .exit();
+        }
+        Mode::Hash(args) if args.zero && output.output_format != OutputFormat::Text => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--zero only applies to the text output format",
+                )
+                .exit();
+        }
+        Mode::Hash(args)
+            if sfv && (output.encoding != Encoding::Hex || args.uppercase || args.multihash) =>
+        {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "SFV files hold uppercase hex checksums, which can't be changed",
+                )
+                .exit();
+        }
+        Mode::Check(manifests, verify) if verify.verify_sig.is_some() && manifests.len() > 1 => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--verify-sig only applies to a single manifest",
+                )
+                .exit();
+        }
+        Mode::Expect(args) if args.expect.len() != args.file_path.len() => {
+            Args::command()
+                .error(
+                    ErrorKind::WrongNumberOfValues,
+                    format!(
+                        "--expect must be given once per FILE, in the same order, not {} DIGESTs for {} FILEs",
+                        args.expect.len(),
+                        args.file_path.len()
+                    ),
+                )
+                .exit();
+        }
+        Mode::Expect(_) if checksum_types.len() > 1 => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--expect can only be checked with a single --type",
+                )
+                .exit();
+        }
+        Mode::Expect(_) if output.output_format == OutputFormat::Sfv => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "the sfv output format doesn't apply to --expect",
+                )
+                .exit();
+        }
+        Mode::Check(..) if output.output_format == OutputFormat::Sfv => {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "the sfv output format only applies to hash mode, SFV manifests are checked by their extension",
+                )
+                .exit();
+        }
+        _ => {}
+    }
+    for external in &algorithm.external_hasher {
+        if Checksum::parse_with_length(&external.name).is_ok() {
+            return Err(ExternalHasherError::BuiltinName(external.name.clone()).into());
+        }
+    }
+
+    let key = match (&algorithm.key, &algorithm.key_file, &algorithm.key_env) {
+        (Some(key), _, _) => Some(hex::decode(key).context("the key must be hex-encoded")?),
+        (_, Some(path), _) => Some(
+            fs::read(path)
+                .with_context(|| format!("failed to read key file {}", path.display()))?,
+        ),
+        (_, _, Some(var)) => {
+            let key =
+                env::var(var).with_context(|| format!("failed to read the key from ${var}"))?;
+            Some(hex::decode(key).with_context(|| format!("${var} must be hex-encoded"))?)
+        }
+        (None, None, None) => None,
+    };
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        const BLAKE2B_VALUES: &[&str] = &["blake", "b2", "blake2", "blake2b"];
+    // the parameters shared by every algorithm
+    let shared = HashOptions {
+        key,
+        seed: algorithm.seed,
+        hmac: algorithm.hmac,
+        salt: algorithm
+            .salt
+            .as_deref()
+            .map(hex::decode)
+            .transpose()
+            .context("the salt must be hex-encoded")?,
+        personal: algorithm
+            .personal
+            .as_deref()
+            .map(hex::decode)
+            .transpose()
+            .context("the personalization must be hex-encoded")?,
+        context: algorithm.derive_key.clone(),
+        customization: algorithm.customization.clone(),
+        ..Default::default()
+    };
 
-        if BLAKE2B_VALUES.contains(&s.to_ascii_lowercase().as_str()) {
-            Ok(Self::Blake2b)
-        } else if s.eq_ignore_ascii_case("sha") {
-            Ok(Self::Sha)
-        } else if s.eq_ignore_ascii_case("md5") {
-            Ok(Self::Md5)
-        } else if s.eq_ignore_ascii_case("sha3") {
-            Ok(Self::Sha3)
-        } else {
-            Err(ParseChecksumError { value: s.into() })
+    let mut algorithms = Vec::with_capacity(checksum_types.len());
+    for checksum_type in checksum_types {
+        let external = algorithm
+            .external_hasher
+            .iter()
+            .find(|external| external.name.eq_ignore_ascii_case(checksum_type))
+            .cloned();
+        let (checksum, named_length) = match external {
+            Some(_) => (Checksum::External, None),
+            None => Checksum::parse_with_length(checksum_type)?,
+        };
+        let bit_length = match (algorithm.bit_length, named_length) {
+            (Some(bit_length), Some(named)) if bit_length != named => {
+                return Err(HashOptionsError::ConflictingBitLength {
+                    name: checksum_type.to_owned(),
+                    bit_length,
+                }
+                .into());
+            }
+            (bit_length, named) => bit_length
+                .or(named)
+                .unwrap_or_else(|| checksum.default_bits()),
+        };
+
+        if checksum.is_legacy() {
+            eprintln!(
+                "warning: {checksum} is a broken legacy algorithm and should only be used to compare against old manifests"
+            );
         }
+
+        let options = HashOptions {
+            bit_length,
+            multithreaded: checksum.supports_multithreading() && resolve_jobs(input.jobs()) > 1,
+            external,
+            ..shared.clone()
+        };
+        algorithms.push((checksum, options));
     }
-}
 
-fn main() -> Result<(), Error> {
-    let args = Args::parse();
+    // before any thread is spawned, so that they inherit it
+    if input.nice_io
+        && let Err(e) = priority::set_idle_io_priority()
+    {
+        eprintln!("warning: failed to lower the I/O priority: {e}");
+    }
 
-    let checksum = Checksum::from_str(&args.checksum_type)?;
-    let bit_length = args.bit_length.unwrap_or_else(|| checksum.default_bits());
+    // fail before hashing anything, even if no line of the manifests is an entry
+    for (checksum, options) in &algorithms {
+        if *checksum != Checksum::External {
+            checksum.hasher(options)?;
+        }
+    }
+    if sfv
+        && let Some((checksum, options)) = algorithms.iter().find(|(checksum, options)| {
+            *checksum != Checksum::Crc32 || options.key.is_some() || options.hmac
+        })
+    {
+        return Err(ManifestError::NotSfvAlgorithm(checksum.full_name(options)).into());
+    }
+    if let Mode::Hash(args) = mode
+        && args.multihash
+        && let Some((checksum, options)) = algorithms
+            .iter()
+            .find(|(checksum, options)| checksum.multicodec(options).is_none())
+    {
+        return Err(EncodingError::NoMulticodec(checksum.full_name(options)).into());
+    }
 
-    for file in &args.file_path {
-        if args.check {
-            check_files(checksum, file, bit_length)?;
-        } else {
-            checksum_files(checksum, &args, file, bit_length)?;
+    if algorithms.iter().any(|(_, options)| options.multithreaded)
+        || (detect && resolve_jobs(input.jobs()) > 1)
+    {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(resolve_jobs(input.jobs()))
+            .build_global()?;
+    }
+
+    let mut input_options = InputOptions {
+        mmap: input.mmap,
+        drop_cache: input.drop_cache,
+        limiter: input.limit_rate.map(RateLimiter::new),
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        io_uring: input.io_uring,
+        ..Default::default()
+    };
+    if let Some(buffer_size) = input.buffer_size {
+        input_options.buffer_size =
+            usize::try_from(buffer_size).context("the buffer size doesn't fit in memory")?;
+    }
+
+    let color = mode.checks() && output.color.unwrap_or_default().enabled();
+    let terminator = match mode {
+        Mode::Hash(args) if args.zero => '\0',
+        _ => '\n',
+    };
+    // the files are found before the outputs are created, so that a walk doesn't list them
+    let outputs = output_paths(mode, &algorithms);
+    let mut files = match mode {
+        Mode::Hash(args) => hash_operands(args, &outputs)?,
+        Mode::Check(..) | Mode::Expect(_) => Vec::new(),
+    };
+    let printers = destinations(mode, &outputs)?
+        .into_iter()
+        .map(|out| {
+            let check = mode.checks();
+            Printer::new(out, output.output_format, check, color, terminator)
+        })
+        .collect();
+    let mut state = RunState {
+        stats: Stats::new(),
+        checkpoint: None,
+        resumed: Resumed::new(),
+        printers,
+        summary: Summary::default(),
+        report: None,
+        sources: HashMap::new(),
+    };
+    if let Mode::Check(
+        _,
+        VerifyArgs {
+            report_file: Some(path),
+            ..
+        },
+    ) = mode
+    {
+        let report = Report::create(path.clone())
+            .with_context(|| format!("failed to create report {}", path.display()))?;
+        state.report = Some(report);
+    }
+
+    if let Some(path) = &input.checkpoint {
+        // resuming with other options would mix up different outputs
+        let run = match mode {
+            Mode::Hash(args) => format!(
+                "steadyhash hash {} {} {}{}{}{}{}{}{}{}",
+                full_names(&algorithms),
+                output.encoding,
+                output.output_format,
+                if args.bsd { " bsd" } else { "" },
+                if args.binary { " binary" } else { "" },
+                if input.text { " text" } else { "" },
+                if args.uppercase { " uppercase" } else { "" },
+                if args.multihash { " multihash" } else { "" },
+                if args.zero { " zero" } else { "" },
+                args.format
+                    .as_ref()
+                    .map(|template| format!(" format {template}"))
+                    .unwrap_or_default(),
+            ),
+            Mode::Check(..) | Mode::Expect(_) => format!(
+                "steadyhash check {} {} {}{}",
+                full_names(&algorithms),
+                output.encoding,
+                output.output_format,
+                if input.text { " text" } else { "" },
+            ),
+        };
+        let (checkpoint, resumed) = Checkpoint::open(path, &run, input.resume)
+            .with_context(|| format!("failed to open checkpoint {}", path.display()))?;
+        state.checkpoint = Some(checkpoint);
+        state.resumed = resumed;
+    }
+
+    match mode {
+        Mode::Hash(args) => {
+            match &args.update {
+                Some(manifest) => {
+                    let (checksum, options) = &algorithms[0];
+                    let format = DigestFormat {
+                        encoding: output.encoding,
+                        bit_length: (*checksum != Checksum::External).then_some(options.bit_length),
+                    };
+                    let tag = args.bsd.then(|| checksum.full_name(options));
+                    let mut update = update::plan(
+                        manifest,
+                        args.base_dir.as_deref(),
+                        *checksum,
+                        format,
+                        tag.as_deref(),
+                        &files,
+                    )
+                    .with_context(|| format!("failed to read manifest {}", manifest.display()))?;
+                    // the new files would otherwise follow the listed ones
+                    if args.sort {
+                        files::sort(&mut update.files);
+                    }
+                    // the lines of unchanged files are copied like results from a checkpoint
+                    state.resumed = update.unchanged;
+                    state.sources = update.sources;
+                    checksum_files(&algorithms, args, &update.files, &input_options, &mut state)?;
+                }
+                None => {
+                    checksum_strings(&algorithms, args, &mut state)?;
+                    // like coreutils, stdin is hashed without FILEs, unless nothing is piped into it
+                    if args.stdin
+                        || (files.is_empty()
+                            && args.files_from.is_none()
+                            && args.string.is_empty()
+                            && !io::stdin().is_terminal())
+                    {
+                        files = stdin.to_vec();
+                    }
+                    checksum_files(&algorithms, args, &files, &input_options, &mut state)?;
+                }
+            }
+        }
+        Mode::Check(manifests, verify) => {
+            // nothing is checked against a manifest that may have been tampered with
+            let verified = match (&verify.verify_sig, &verify.pubkey) {
+                (Some(signature), Some(pubkey)) => {
+                    Some(signature::read_verified(&manifests[0], signature, pubkey)?)
+                }
+                _ => None,
+            };
+            let check_options = CheckOptions {
+                input,
+                output,
+                verify,
+                detect,
+            };
+            for file in manifests {
+                check_files(
+                    &algorithms[0],
+                    file,
+                    verified.as_deref(),
+                    &input_options,
+                    &check_options,
+                    &mut state,
+                )?;
+            }
+
+            if let Some(summary) = output::format_summary(output.output_format, &state.summary) {
+                state.printers[0].print(&summary)?;
+            }
+            if let Some(report) = state.report.take() {
+                report.finish(&state.summary)?;
+            }
+            if !verify.status {
+                for warning in state.summary.warnings() {
+                    eprintln!("{warning}");
+                }
+            }
+        }
+        Mode::Expect(args) => {
+            let verify = VerifyArgs::default();
+            let check_options = CheckOptions {
+                input,
+                output,
+                verify: &verify,
+                detect,
+            };
+            let (_, options) = &algorithms[0];
+            let batch: Vec<_> = args
+                .file_path
+                .iter()
+                .zip(&args.expect)
+                .enumerate()
+                .map(|(index, (path, digest))| {
+                    let entry = Entry {
+                        expected: output
+                            .encoding
+                            .to_hex(digest)
+                            .unwrap_or_else(|| digest.clone()),
+                        path: path.display().to_string().into(),
+                        binary: !input.text,
+                        tag: None,
+                    };
+                    let detected = detect
+                        .then(|| detect_algorithm(&entry, options, resolve_jobs(input.jobs()) > 1))
+                        .flatten();
+                    PendingEntry {
+                        entry,
+                        line: index + 1,
+                        detected,
+                        path: path.clone(),
+                    }
+                })
+                .collect();
+            check_batch(
+                &algorithms[0],
+                Path::new("-"),
+                &batch,
+                &input_options,
+                &check_options,
+                &mut state,
+            )?;
+
+            if let Some(summary) = output::format_summary(output.output_format, &state.summary) {
+                state.printers[0].print(&summary)?;
+            }
+            for warning in state.summary.warnings() {
+                eprintln!("{warning}");
+            }
         }
     }
 
-    Ok(())
+    for printer in state.printers {
+        printer.finish()?;
+    }
+    if let Some(checkpoint) = state.checkpoint {
+        checkpoint.finish()?;
+    }
+    if let Some(format) = input.stats {
+        eprint!("{}", state.stats.report(format));
+    }
+
+    Ok(state.summary)
 }
 
-fn check_files(checksum: Checksum, file: &Path, bit_length: usize) -> Result<(), Error> {
-    let mut reader = BufReader::new(File::open(file)?);
+/// Names of the algorithms of a run, as shown in checkpoints and statistics.
+fn full_names(algorithms: &[(Checksum, HashOptions)]) -> String {
+    algorithms
+        .iter()
+        .map(|(checksum, options)| checksum.full_name(options))
+        .collect::<Vec<_>>()
+        .join("+")
+}
 
-    let mut contents = String::new();
+/// State kept across every file of a run.
+struct RunState {
+    stats: Stats,
 
-    reader.read_to_string(&mut contents)?;
+    /// Checkpoint recording the files processed so far
+    checkpoint: Option<Checkpoint>,
 
-    for line in contents.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
+    /// Outputs of the files processed by the interrupted run being resumed
+    resumed: Resumed,
 
-        if parts.len() < 2 {
-            continue;
-        }
+    /// Printers of the output, one per algorithm if they're written to separate files
+    printers: Vec<Printer>,
 
-        let (expected_checksum, file_path) = if parts.len() >= 2 && parts[1].starts_with('(') {
-            // BSD style
+    /// Results of check mode
+    summary: Summary,
 
-            let file_path = parts[1].trim_start_matches('(').trim_end_matches(')');
+    /// Report of check mode, with `--report-file`
+    report: Option<Report>,
 
-            (parts[3], file_path)
-        } else {
-            // default style
+    /// Paths the files listed in the manifest of `--update` are read from, by the path written in
+    /// it, when they aren't relative to the current directory
+    sources: HashMap<PathBuf, PathBuf>,
+}
+
+/// The files the output of a run is written to: the file given to `--output` or `--update`, or one
+/// file per algorithm if its path contains `{algo}`. There are none if it goes to stdout.
+fn output_paths(mode: Mode, algorithms: &[(Checksum, HashOptions)]) -> Vec<PathBuf> {
+    let path = match mode {
+        Mode::Hash(HashArgs {
+            output_path: Some(path),
+            ..
+        })
+        | Mode::Hash(HashArgs {
+            update: Some(path), ..
+        }) => path,
+        _ => return Vec::new(),
+    };
+    match path.to_str() {
+        Some(template) if template.contains("{algo}") => algorithms
+            .iter()
+            .map(|(checksum, options)| {
+                let name = checksum.full_name(options).to_ascii_lowercase();
+                template.replace("{algo}", &name).into()
+            })
+            .collect(),
+        _ => vec![path.clone()],
+    }
+}
+
+/// Where the output of a run goes: stdout, or the files of `outputs`. With `--status`, it's
+/// discarded.
+fn destinations(mode: Mode, outputs: &[PathBuf]) -> Result<Vec<Destination>, Error> {
+    match mode {
+        Mode::Check(_, verify) if verify.status => return Ok(vec![Destination::Sink]),
+        _ if outputs.is_empty() => return Ok(vec![Destination::Stdout]),
+        _ => {}
+    }
+    outputs
+        .iter()
+        .map(|path| {
+            AtomicFile::create(path.clone())
+                .map(Destination::File)
+                .with_context(|| format!("failed to create {}", path.display()))
+        })
+        .collect()
+}
 
-            (parts[0], parts[1])
+/// The files to hash: the FILEs, those listed in `--files-from`, and with `--recursive`, the files
+/// under the directories among them, except for `outputs`, the manifests the run writes.
+fn hash_operands(args: &HashArgs, outputs: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = args.file_path.clone();
+    if let Some(list) = &args.files_from {
+        files.extend(files::read_list(list, args.null)?);
+    }
+    if args.recursive {
+        let mut exclude = args.exclude.clone();
+        if let Some(path) = &args.exclude_from {
+            exclude.extend(files::read_patterns(path)?);
+        }
+        let options = WalkOptions {
+            include: args.include.clone(),
+            exclude,
+            max_depth: args.max_depth,
+            skip_hidden: args.no_hidden,
+            follow_symlinks: args.follow_symlinks,
+            keep_symlinks: false,
+            // the manifests of earlier runs would otherwise list themselves
+            skip: outputs
+                .iter()
+                .filter_map(|output| fs::canonicalize(output).ok())
+                .collect(),
         };
+        files = files::expand(files, &options)?;
+    }
+    if args.sort {
+        files::sort(&mut files);
+    }
+
+    Ok(files)
+}
+
+/// Finds the algorithm of an entry from its BSD-style tag, or else guesses it from the length of
+/// its digest. The other options are kept from `options`.
+fn detect_algorithm(
+    entry: &Entry,
+    options: &HashOptions,
+    parallel: bool,
+) -> Option<(Checksum, HashOptions)> {
+    let (checksum, bit_length) = match &entry.tag {
+        Some(tag) => {
+            let (checksum, bit_length) = Checksum::parse_with_length(tag).ok()?;
+            (
+                checksum,
+                bit_length.unwrap_or_else(|| checksum.default_bits()),
+            )
+        }
+        None if entry.expected.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            let bit_length = entry.expected.len() * 4;
+            (Checksum::guess(bit_length)?, bit_length)
+        }
+        None => return None,
+    };
+
+    Some((
+        checksum,
+        HashOptions {
+            bit_length,
+            multithreaded: parallel && checksum.supports_multithreading(),
+            ..options.clone()
+        },
+    ))
+}
 
-        let mut file_contents = Vec::new();
+/// The options of check mode.
+struct CheckOptions<'a> {
+    input: &'a InputArgs,
+    output: &'a OutputArgs,
+    verify: &'a VerifyArgs,
 
-        let mut reader = BufReader::new(File::open(file_path)?);
+    /// Whether the algorithm of each entry is found from its tag or digest, as none was given
+    detect: bool,
+}
+
+/// Number of entries of a manifest read before checking them, so that with `--jobs`, the files
+/// are checked in parallel while the manifest is still streamed.
+const CHECK_BATCH: usize = 1024;
+
+/// An entry of a manifest waiting to be checked.
+struct PendingEntry {
+    entry: Entry<'static>,
+
+    /// Number of its line in the manifest
+    line: usize,
+
+    /// Algorithm found for the entry, if it isn't the one of the run
+    detected: Option<(Checksum, HashOptions)>,
+
+    /// Path of the file, resolved against the base directory
+    path: PathBuf,
+}
+
+impl PendingEntry {
+    /// The line of the report for the entry.
+    fn report<'a>(
+        &'a self,
+        manifest: &'a Path,
+        algorithm: &'a str,
+        status: &'a str,
+        actual: Option<&'a str>,
+    ) -> ReportEntry<'a> {
+        ReportEntry {
+            manifest,
+            line: self.line,
+            status,
+            path: Some(&self.entry.path),
+            algorithm: Some(algorithm),
+            expected: Some(&self.entry.expected),
+            actual,
+        }
+    }
+}
+
+/// What became of a file listed in a manifest.
+enum Checked {
+    /// Its result was read from the checkpoint
+    Resumed,
+
+    /// It doesn't exist, and `--ignore-missing` was given
+    Missing,
 
-        reader.read_to_end(&mut file_contents)?;
+    Hashed(Result<Hashed, Error>),
+}
+
+/// Checks the entries of the manifest `file` as its lines are read, a batch at a time. Like the
+/// manifest, the files are streamed through a fixed-size buffer, so neither is ever held in memory
+/// whole, unless the manifest was read as `contents` to verify its signature.
+fn check_files(
+    algorithm: &(Checksum, HashOptions),
+    file: &Path,
+    contents: Option<&[u8]>,
+    input_options: &InputOptions,
+    check_options: &CheckOptions,
+    state: &mut RunState,
+) -> Result<(), Error> {
+    let CheckOptions {
+        input,
+        output,
+        verify,
+        detect,
+    } = check_options;
+    let (checksum, options) = algorithm;
+    let checksum = *checksum;
+    let sfv = is_sfv(file);
+    let reader: Box<dyn BufRead> = match contents {
+        Some(contents) => Box::new(contents),
+        None if file == Path::new("-") => Box::new(io::stdin().lock()),
+        None => Box::new(BufReader::new(File::open(file)?)),
+    };
+    let (mut entries, mut missing, mut malformed) = (0, 0, 0);
 
-        let actual_checksum = checksum.calculate(bit_length, &file_contents)?;
+    // the lengths of the digests only vary if the algorithm of each line is detected
+    let format = DigestFormat {
+        encoding: output.encoding,
+        bit_length: (!detect && checksum != Checksum::External).then_some(options.bit_length),
+    };
+    let delimiter = if verify.zero { b'\0' } else { b'\n' };
+    let mut batch = Vec::new();
+    let mut clearsign = Clearsign::default();
 
-        if actual_checksum == expected_checksum {
-            println!("{file_path}: OK");
+    for (index, line) in reader.split(delimiter).enumerate() {
+        let line =
+            String::from_utf8(line?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let Some(line) = clearsign.strip(&line) else {
+            continue;
+        };
+        let entry = if sfv {
+            manifest::parse_sfv_line(line)
         } else {
-            println!("{file_path}: FAILED");
+            manifest::parse_line_as(checksum, format, line)
+        };
+        // lines of which the algorithm can't be found, like those with an unknown tag, are
+        // malformed too
+        let parallel = resolve_jobs(input.jobs()) > 1;
+        let parsed = entry.and_then(|entry| {
+            let detected = if *detect {
+                Some(detect_algorithm(&entry, options, parallel)?)
+            } else {
+                None
+            };
+            Some((entry, detected))
+        });
+        let Some((entry, detected)) = parsed else {
+            // blank lines and comments aren't entries, but aren't malformed either
+            let line = line.trim();
+            if !line.is_empty() && !line.starts_with(if sfv { ';' } else { '#' }) {
+                state.summary.malformed += 1;
+                malformed += 1;
+                if let Some(report) = &mut state.report {
+                    report.record(&ReportEntry {
+                        manifest: file,
+                        line: index + 1,
+                        status: "malformed",
+                        path: None,
+                        algorithm: None,
+                        expected: None,
+                        actual: None,
+                    })?;
+                }
+                if verify.warn {
+                    let name = if sfv {
+                        "SFV ".to_owned()
+                    } else if *detect {
+                        String::new()
+                    } else {
+                        format!("{} ", checksum.full_name(options))
+                    };
+                    eprintln!(
+                        "warning: {}: {}: improperly formatted {name}checksum line",
+                        file.display(),
+                        index + 1,
+                    );
+                }
+            }
+            continue;
+        };
+        entries += 1;
+        let path = manifest::resolve_path(file, verify.base_dir.as_deref(), &entry.path);
+        batch.push(PendingEntry {
+            entry: entry.into_owned(),
+            line: index + 1,
+            detected,
+            path,
+        });
+
+        if batch.len() == CHECK_BATCH {
+            missing += check_batch(algorithm, file, &batch, input_options, check_options, state)?;
+            batch.clear();
         }
     }
+    missing += check_batch(algorithm, file, &batch, input_options, check_options, state)?;
+
+    // like coreutils, a manifest of which every file is missing isn't a success
+    if missing > 0 && missing == entries {
+        eprintln!("error: {}: no file was verified", file.display());
+        state.summary.unverified += 1;
+    }
+    // nor is a manifest of which every line is malformed, like a truncated or corrupted one
+    if entries == 0 && malformed > 0 {
+        eprintln!(
+            "error: {}: no properly formatted checksum lines found",
+            file.display()
+        );
+        state.summary.unverified += 1;
+    }
 
     Ok(())
 }
 
+/// Checks a batch of entries on up to `--jobs` threads, printing the results in order. Returns the
+/// number of missing files.
+fn check_batch(
+    algorithm: &(Checksum, HashOptions),
+    manifest: &Path,
+    batch: &[PendingEntry],
+    input_options: &InputOptions,
+    check_options: &CheckOptions,
+    state: &mut RunState,
+) -> Result<usize, Error> {
+    let CheckOptions {
+        input,
+        output,
+        verify,
+        ..
+    } = check_options;
+    let RunState {
+        stats,
+        checkpoint,
+        resumed,
+        printers,
+        summary,
+        report,
+        ..
+    } = state;
+    let jobs = resolve_jobs(input.jobs());
+    let mut missing = 0;
+
+    // with several jobs, a bar follows the whole batch rather than each file
+    let bar = if jobs > 1 {
+        let size = batch
+            .iter()
+            .filter_map(|pending| fs::metadata(&pending.path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        input
+            .progress
+            .enabled(size)
+            .then(|| ProgressBar::for_files(batch.len(), size))
+    } else {
+        None
+    };
+
+    let result = for_each_ordered(
+        batch,
+        jobs,
+        |pending| {
+            if resumed.contains_key(&*pending.path.to_string_lossy()) {
+                return Checked::Resumed;
+            }
+
+            let metadata = fs::metadata(&pending.path);
+            if verify.ignore_missing
+                && matches!(&metadata, Err(e) if e.kind() == io::ErrorKind::NotFound)
+            {
+                return Checked::Missing;
+            }
+            let size = metadata.map_or(0, |metadata| metadata.len());
+            let file_bar = (jobs == 1 && input.progress.enabled(size))
+                .then(|| ProgressBar::for_file(pending.entry.path.to_string(), size));
+            // like coreutils, files without the binary mode marker are read in text mode on Windows
+            let text = (input.text || cfg!(windows)) && !pending.entry.binary;
+            let hashed = hash_file(
+                slice::from_ref(pending.detected.as_ref().unwrap_or(algorithm)),
+                &pending.path,
+                input_options,
+                text,
+                file_bar.as_ref().or(bar.as_ref()),
+            );
+            if let Some(file_bar) = file_bar {
+                file_bar.finish();
+            }
+            Checked::Hashed(hashed)
+        },
+        |pending, checked| {
+            let (checksum, options) = pending.detected.as_ref().unwrap_or(algorithm);
+            let name = checksum.full_name(options);
+            let file_path: &str = &pending.entry.path;
+            let key = pending.path.to_string_lossy();
+            if let Some(bar) = &bar {
+                bar.file_done();
+            }
+
+            let (status, result, actual) = match checked {
+                // results from the checkpoint are recorded with their status
+                Checked::Resumed => {
+                    let (status, result) = match resumed[&*key].as_slice() {
+                        [status, result] => {
+                            Status::from_name(status).map(|status| (status, result))
+                        }
+                        _ => None,
+                    }
+                    .ok_or_else(|| CheckpointError::InvalidResult(file_path.to_owned()))?;
+                    (status, Cow::Borrowed(result.as_str()), None)
+                }
+                Checked::Missing => {
+                    summary.missing += 1;
+                    summary.ignored += 1;
+                    missing += 1;
+                    if let Some(report) = report {
+                        report.record(&pending.report(manifest, &name, "missing", None))?;
+                    }
+                    return Ok(());
+                }
+                Checked::Hashed(hashed) => {
+                    let (verification, actual) = match hashed {
+                        Ok(mut hashed) => {
+                            stats.record(&name, hashed.bytes, hashed.time);
+                            let actual = hashed.checksums.swap_remove(0);
+                            (Verification::new(&pending.entry, &actual), Some(actual))
+                        }
+                        // like coreutils, go on with the other files
+                        Err(e) => {
+                            eprintln!("error: failed to read {}: {e:#}", pending.path.display());
+                            let not_found = e
+                                .root_cause()
+                                .downcast_ref::<io::Error>()
+                                .is_some_and(|e| e.kind() == io::ErrorKind::NotFound);
+                            let verification = Verification {
+                                path: file_path.to_owned(),
+                                status: if not_found {
+                                    Status::Missing
+                                } else {
+                                    Status::Unreadable
+                                },
+                            };
+                            (verification, None)
+                        }
+                    };
+                    let result =
+                        output::format_verification(output.output_format, &verification, &name);
+                    if let Some(checkpoint) = checkpoint {
+                        checkpoint.record(&key, &[verification.status.name(), &result])?;
+                    }
+                    (verification.status, Cow::Owned(result), actual)
+                }
+            };
+            summary.record(status);
+            if let Some(report) = report {
+                let entry = pending.report(manifest, &name, status.name(), actual.as_deref());
+                report.record(&entry)?;
+            }
+
+            if !(verify.quiet && status == Status::Ok) {
+                let mut print = || printers[0].print(&result);
+                match &bar {
+                    Some(bar) => bar.suspend(print)?,
+                    None => print()?,
+                }
+            }
+            Ok::<_, Error>(())
+        },
+    );
+
+    if let Some(bar) = bar {
+        bar.finish();
+    }
+    result.map(|()| missing)
+}
+
+/// Whether a manifest is an SFV file, from its extension.
+fn is_sfv(manifest: &Path) -> bool {
+    manifest
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("sfv"))
+}
+
 fn checksum_files(
-    checksum: Checksum,
-    args: &Args,
-    file: &Path,
-    bit_length: usize,
+    algorithms: &[(Checksum, HashOptions)],
+    args: &HashArgs,
+    files: &[PathBuf],
+    input_options: &InputOptions,
+    state: &mut RunState,
 ) -> Result<(), Error> {
-    let mut contents = Vec::new();
+    let RunState {
+        stats,
+        checkpoint,
+        resumed,
+        printers,
+        summary,
+        sources,
+        ..
+    } = state;
+    // the files listed in the manifest of `--update` may be read from another directory
+    let source = |file: &Path| {
+        sources
+            .get(file)
+            .cloned()
+            .unwrap_or_else(|| file.to_owned())
+    };
+
+    // several checksums of a file can't be told apart without the algorithm names, unless they're
+    // written to separate files
+    let style = manifest::LineStyle {
+        bsd: args.bsd || printers.len() < algorithms.len(),
+        binary: args.binary,
+        raw_paths: args.zero,
+    };
+    let name = full_names(algorithms);
 
-    if args.stdin {
-        io::stdin().read_to_end(&mut contents)?;
+    let text = args.input.text || (cfg!(windows) && !args.binary);
+    let separator = if args.zero {
+        "\0"
     } else {
-        let mut reader = BufReader::new(File::open(file)?);
-        reader.read_to_end(&mut contents)?;
+        args.output.output_format.separator()
+    };
+
+    // the sizes are only known upfront for files
+    let bar = match files {
+        [file] if file == Path::new("-") => None,
+        [file] => {
+            let size = fs::metadata(source(file)).map_or(0, |metadata| metadata.len());
+            args.input
+                .progress
+                .enabled(size)
+                .then(|| ProgressBar::for_file(file.display().to_string(), size))
+        }
+        files => {
+            let size = files
+                .iter()
+                .filter(|file| !resumed.contains_key(&file.display().to_string()))
+                .filter_map(|file| fs::metadata(source(file)).ok())
+                .map(|metadata| metadata.len())
+                .sum();
+            args.input
+                .progress
+                .enabled(size)
+                .then(|| ProgressBar::for_files(files.len(), size))
+        }
+    };
+
+    let result = for_each_ordered(
+        files,
+        args.input.jobs(),
+        |file| {
+            if resumed.contains_key(&file.display().to_string()) {
+                return Ok(None);
+            }
+
+            hash_file(algorithms, &source(file), input_options, text, bar.as_ref()).map(Some)
+        },
+        |file, hashed: Result<Option<Hashed>, Error>| {
+            let entry = file.display().to_string();
+            let hashed = match hashed {
+                // like coreutils, go on with the other files, but fail in the end
+                Err(e) if e.root_cause().is::<io::Error>() || e.is::<Sp800185SumError>() => {
+                    if let Some(bar) = &bar {
+                        bar.file_done();
+                    }
+                    eprintln!("error: failed to read {}: {e:#}", file.display());
+                    summary.unreadable += 1;
+                    return Ok(());
+                }
+                hashed => hashed?,
+            };
+            // one output per algorithm, as they may be printed to separate files
+            let outputs = match hashed {
+                Some(hashed) if args.raw => {
+                    stats.record(&name, hashed.bytes, hashed.time);
+                    let raw = hex::decode(&hashed.checksums[0])
+                        .map_err(|_| EncodingError::NotBinary(name.clone()))?;
+                    io::stdout().write_all(&raw)?;
+                    return Ok(());
+                }
+                Some(hashed) => {
+                    stats.record(&name, hashed.bytes, hashed.time);
+                    let mtime = (args.output.output_format.delimiter().is_some()
+                        || args.format.as_ref().is_some_and(Template::uses_mtime))
+                    .then(|| fs::metadata(source(file)).and_then(|metadata| metadata.modified()))
+                    .and_then(Result::ok);
+                    let mut outputs = algorithms
+                        .iter()
+                        .zip(&hashed.checksums)
+                        .map(|((checksum, options), hex)| {
+                            let encoded = encode_checksum(args, *checksum, options, hex)?;
+                            let record = Record {
+                                path: file,
+                                algorithm: &checksum.full_name(options),
+                                bit_length: options.bit_length,
+                                checksum: &encoded,
+                                size: hashed.bytes,
+                                mtime,
+                            };
+                            let format = args.output.output_format;
+                            Ok(match (format, format.delimiter(), &args.format) {
+                                (_, Some(delimiter), _) => record.to_csv(delimiter),
+                                (OutputFormat::Sfv, _, _) => record.to_sfv(),
+                                (OutputFormat::Text, _, Some(template)) => template.render(&record),
+                                (OutputFormat::Text, _, None) => {
+                                    manifest::format_line(*checksum, options, file, &encoded, style)
+                                }
+                                _ => record.to_json(),
+                            })
+                        })
+                        .collect::<Result<Vec<_>, EncodingError>>()?;
+                    // the next update tells modified files apart by their size too
+                    if args.update.is_some() {
+                        outputs = outputs
+                            .iter()
+                            .map(|output| update::with_size(output, hashed.bytes))
+                            .collect();
+                    }
+                    if let Some(checkpoint) = checkpoint {
+                        checkpoint.record(&entry, &outputs)?;
+                    }
+                    outputs
+                }
+                // files from the checkpoint aren't hashed again
+                None => resumed[&entry].clone(),
+            };
+
+            // the checksums of each algorithm go to their own file
+            let mut print = || match printers.as_mut_slice() {
+                [printer] => printer.print(&outputs.join(separator)),
+                printers => printers
+                    .iter_mut()
+                    .zip(&outputs)
+                    .try_for_each(|(printer, output)| printer.print(output)),
+            };
+            match &bar {
+                Some(bar) => {
+                    bar.file_done();
+                    bar.suspend(print)?;
+                }
+                None => print()?,
+            }
+            Ok(())
+        },
+    );
+
+    if let Some(bar) = bar {
+        bar.finish();
     }
+    result
+}
 
-    let checksum_str = checksum.calculate(bit_length, &contents)?;
-    checksum.print(bit_length, file, &checksum_str, args.bsd);
+/// Prints the checksums of the strings given to `--string`, like BSD `md5 -s`: each line holds
+/// the checksum and the quoted string, or with `--bsd`, looks like `SHA256 ("TEXT") = HASH`.
+fn checksum_strings(
+    algorithms: &[(Checksum, HashOptions)],
+    args: &HashArgs,
+    state: &mut RunState,
+) -> Result<(), Error> {
+    let RunState {
+        stats, printers, ..
+    } = state;
+    // like files, several checksums of a string need the algorithm names to be told apart
+    let bsd = args.bsd || printers.len() < algorithms.len();
+    let name = full_names(algorithms);
+
+    for string in &args.string {
+        let start = Instant::now();
+        let checksums = calculate_all(algorithms, Input::Bytes(string.as_bytes()))?;
+        stats.record(&name, string.len() as u64, start.elapsed());
+
+        let lines = algorithms
+            .iter()
+            .zip(&checksums)
+            .map(|((checksum, options), hex)| {
+                let encoded = encode_checksum(args, *checksum, options, hex)?;
+                let (prefix, quoted) = manifest::quote_string(string);
+                Ok(if bsd {
+                    format!(
+                        "{prefix}{} ({quoted}) = {encoded}",
+                        checksum.full_name(options)
+                    )
+                } else {
+                    format!("{prefix}{encoded} {quoted}")
+                })
+            })
+            .collect::<Result<Vec<_>, EncodingError>>()?;
+        match printers.as_mut_slice() {
+            [printer] => lines.iter().try_for_each(|line| printer.print(line))?,
+            printers => printers
+                .iter_mut()
+                .zip(&lines)
+                .try_for_each(|(printer, line)| printer.print(line))?,
+        }
+    }
 
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Writes a checksum as the output options of `hash` ask for.
+fn encode_checksum<'a>(
+    args: &HashArgs,
+    checksum: Checksum,
+    options: &HashOptions,
+    hex: &'a str,
+) -> Result<Cow<'a, str>, EncodingError> {
+    if args.multihash {
+        let name = || checksum.full_name(options);
+        let code = checksum
+            .multicodec(options)
+            .ok_or_else(|| EncodingError::NoMulticodec(name()))?;
+        let digest = hex::decode(hex).map_err(|_| EncodingError::NotBinary(name()))?;
+        return Ok(args
+            .output
+            .encoding
+            .multibase(&encoding::multihash(code, &digest))
+            .into());
+    }
+
+    Ok(match args.output.encoding {
+        Encoding::Hex if args.uppercase => Cow::Owned(hex.to_ascii_uppercase()),
+        Encoding::Hex => Cow::Borrowed(hex),
+        encoding => Cow::Owned(encoding.encode(hex)),
+    })
+}
+
+/// The checksums of an input, along with what it took to compute them.
+struct Hashed {
+    /// One checksum per algorithm, in the order of the algorithms
+    checksums: Vec<String>,
 
-    #[test]
-    fn checksum_from_str() {
-        // Test with lowercase
-        assert_eq!(Checksum::from_str("sha").unwrap(), Checksum::Sha);
-        assert_eq!(Checksum::from_str("blake2b").unwrap(), Checksum::Blake2b);
-        assert_eq!(Checksum::from_str("md5").unwrap(), Checksum::Md5);
-        assert_eq!(Checksum::from_str("sha3").unwrap(), Checksum::Sha3);
+    /// Number of bytes hashed
+    bytes: u64,
 
-        // Test with uppercase
-        assert_eq!(Checksum::from_str("SHa").unwrap(), Checksum::Sha);
-        assert_eq!(Checksum::from_str("BLake2b").unwrap(), Checksum::Blake2b);
-        assert_eq!(Checksum::from_str("mD5").unwrap(), Checksum::Md5);
-        assert_eq!(Checksum::from_str("sHA3").unwrap(), Checksum::Sha3);
+    /// Time spent opening and hashing the input
+    time: Duration,
+}
+
+/// Hashes the file at `path` with every algorithm in one read, in text mode if `text` is set,
+/// advancing `bar` as it's read. Like in other Unix tools, `-` is stdin.
+fn hash_file(
+    algorithms: &[(Checksum, HashOptions)],
+    path: &Path,
+    input_options: &InputOptions,
+    text: bool,
+    bar: Option<&ProgressBar>,
+) -> Result<Hashed, Error> {
+    if path == Path::new("-") {
+        return hash_stdin(algorithms, input_options, text, bar);
+    }
+
+    // TupleHash would keep the whole file in memory
+    if let Some(limit) = max_input_size(algorithms)
+        && fs::metadata(path)?.len() > limit
+    {
+        return Err(Sp800185SumError::InputTooLarge(limit).into());
     }
+
+    let start = Instant::now();
+    let mut opened = OpenedFile::open(path, input_options)?;
+
+    let limiter = input_options.limiter.as_ref();
+    let (checksums, bytes) = match (opened.input(), limiter, bar) {
+        // mapped files are hashed in one go, unless they're throttled, read in text mode or their
+        // progress is shown
+        (Input::Bytes(data), None, None) if !text => (
+            calculate_all(algorithms, Input::Bytes(data))?,
+            data.len() as u64,
+        ),
+        (input, limiter, bar) => hash_counted(algorithms, input, limiter, text, bar)?,
+    };
+
+    Ok(Hashed {
+        checksums,
+        bytes,
+        time: start.elapsed(),
+    })
+}
+
+/// Largest input the `algorithms` take, if one of them keeps its whole input in memory.
+fn max_input_size(algorithms: &[(Checksum, HashOptions)]) -> Option<u64> {
+    algorithms
+        .iter()
+        .filter_map(|(checksum, _)| checksum.max_input_size())
+        .min()
+}
+
+/// Hashes stdin with every algorithm, in text mode if `text` is set, advancing `bar` as it's read.
+fn hash_stdin(
+    algorithms: &[(Checksum, HashOptions)],
+    input_options: &InputOptions,
+    text: bool,
+    bar: Option<&ProgressBar>,
+) -> Result<Hashed, Error> {
+    let start = Instant::now();
+    // its size isn't known upfront, so it's read up to one byte past the limit
+    let limit = max_input_size(algorithms);
+    let (checksums, bytes) = hash_counted(
+        algorithms,
+        Input::Reader {
+            reader: &mut io::stdin()
+                .lock()
+                .take(limit.map_or(u64::MAX, |limit| limit + 1)),
+            buffer_size: input_options.buffer_size,
+        },
+        input_options.limiter.as_ref(),
+        text,
+        bar,
+    )?;
+    if let Some(limit) = limit
+        && bytes > limit
+    {
+        return Err(Sp800185SumError::InputTooLarge(limit).into());
+    }
+
+    Ok(Hashed {
+        checksums,
+        bytes,
+        time: start.elapsed(),
+    })
+}
+
+/// Hashes `input` through a buffer, reading it no faster than `limiter` allows and in text mode if
+/// `text` is set, and returns the checksums and the number of bytes read.
+fn hash_counted(
+    algorithms: &[(Checksum, HashOptions)],
+    mut input: Input,
+    limiter: Option<&RateLimiter>,
+    text: bool,
+    bar: Option<&ProgressBar>,
+) -> Result<(Vec<String>, u64), Error> {
+    let buffer_size = input.buffer_size();
+    let mut throttled;
+    let input: &mut dyn Read = match limiter {
+        Some(limiter) => {
+            throttled = ThrottledReader::new(input, limiter);
+            &mut throttled
+        }
+        None => &mut input,
+    };
+
+    let mut reader = CountingReader::new(input, bar);
+    let mut text_reader;
+    let checksums = calculate_all(
+        algorithms,
+        Input::Reader {
+            reader: if text {
+                text_reader = TextReader::new(&mut reader, buffer_size);
+                &mut text_reader
+            } else {
+                &mut reader
+            },
+            buffer_size,
+        },
+    )?;
+
+    Ok((checksums, reader.count()))
 }
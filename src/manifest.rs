@@ -0,0 +1,769 @@
+//! Reading and writing manifests, the files listing the checksums of other files, in the default
+//! (coreutils) and BSD styles, and Simple File Verification (SFV) files.
+
+use crate::{Checksum, HashOptions, encoding::Encoding};
+use std::{
+    borrow::Cow,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// An entry of a manifest: the expected checksum of a file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry<'a> {
+    /// Expected checksum. Multi-field checksums, like `cksum`'s, have their fields separated by a
+    /// single space
+    pub expected: String,
+
+    /// Path of the file, as written in the manifest once unescaped
+    pub path: Cow<'a, str>,
+
+    /// Whether the file was read in binary mode, as marked by a `*` in the default style. BSD-style
+    /// lines always are
+    pub binary: bool,
+
+    /// Name of the algorithm of BSD-style lines, like `SHA3-256`
+    pub tag: Option<Cow<'a, str>>,
+}
+
+impl Entry<'_> {
+    /// Copies the parts of the entry borrowed from its line.
+    pub fn into_owned(self) -> Entry<'static> {
+        Entry {
+            expected: self.expected,
+            path: self.path.into_owned().into(),
+            binary: self.binary,
+            tag: self.tag.map(|tag| tag.into_owned().into()),
+        }
+    }
+
+    /// Whether `actual`, as returned by [`Checksum::calculate`], matches the expected checksum.
+    /// Hex digits match in either case.
+    pub fn matches(&self, actual: &str) -> bool {
+        // `sum` pads its fields, so compare multi-field checksums field by field
+        actual
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .eq_ignore_ascii_case(&self.expected)
+    }
+}
+
+/// The checksum computed for a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Digest {
+    /// Name of the algorithm, as in BSD-style lines (e.g. `SHA256` or `HMAC-SHA256`)
+    pub algorithm: String,
+
+    /// Path of the file
+    pub path: String,
+
+    /// The checksum, as returned by [`Checksum::calculate`]
+    pub checksum: String,
+}
+
+/// How the lines of a manifest are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LineStyle {
+    /// Whether to write BSD-style lines, like `SHA256 (foo.bar) = …`
+    pub bsd: bool,
+
+    /// Whether to mark files as read in binary mode, with a `*` before their path in the default
+    /// style
+    pub binary: bool,
+
+    /// Whether to leave paths unescaped, for lines which aren't separated by newlines
+    pub raw_paths: bool,
+}
+
+/// Outcome of checking a file against its expected checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Status {
+    /// The checksum matches
+    Ok,
+
+    /// The checksum is different
+    Failed,
+
+    /// The file couldn't be opened or read
+    Unreadable,
+
+    /// The file doesn't exist
+    Missing,
+}
+
+impl Status {
+    pub const ALL: &[Status] = &[
+        Status::Ok,
+        Status::Failed,
+        Status::Unreadable,
+        Status::Missing,
+    ];
+
+    /// Name of the status in the structured output formats.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Failed => "failed",
+            Status::Unreadable => "unreadable",
+            Status::Missing => "missing",
+        }
+    }
+
+    /// Finds a status by its [name](Status::name).
+    pub fn from_name(name: &str) -> Option<Status> {
+        Status::ALL
+            .iter()
+            .copied()
+            .find(|status| status.name() == name)
+    }
+}
+
+/// The result of checking a file listed in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Verification {
+    /// Path of the file, as written in the manifest
+    pub path: String,
+
+    pub status: Status,
+}
+
+impl Verification {
+    /// Checks the checksum `actual` computed for `entry`.
+    pub fn new(entry: &Entry, actual: &str) -> Verification {
+        Verification {
+            path: entry.path.clone().into_owned(),
+            status: if entry.matches(actual) {
+                Status::Ok
+            } else {
+                Status::Failed
+            },
+        }
+    }
+}
+
+/// Formats the result like coreutils' `--check` does, e.g. `foo.bar: OK`. Like coreutils, paths
+/// are only escaped if they contain line breaks.
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match escape_path(&self.path) {
+            Some(escaped) if self.path.contains(['\n', '\r']) => format!("\\{escaped}").into(),
+            _ => Cow::from(&self.path),
+        };
+        match self.status {
+            Status::Ok => write!(f, "{path}: OK"),
+            Status::Failed => write!(f, "{path}: FAILED"),
+            Status::Unreadable | Status::Missing => write!(f, "{path}: FAILED open or read"),
+        }
+    }
+}
+
+/// Counts of the results of checking manifests.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub ok: usize,
+    pub failed: usize,
+    pub unreadable: usize,
+
+    /// Lines of the manifests which are neither entries, blank nor comments
+    pub malformed: usize,
+
+    /// Files which don't exist, including those skipped with `--ignore-missing`
+    pub missing: usize,
+
+    /// Files skipped with `--ignore-missing` as they don't exist
+    pub ignored: usize,
+
+    /// Manifests of which no file was verified, as every file was missing or every line was
+    /// malformed
+    pub unverified: usize,
+}
+
+impl Summary {
+    pub fn record(&mut self, status: Status) {
+        match status {
+            Status::Ok => self.ok += 1,
+            Status::Failed => self.failed += 1,
+            Status::Unreadable => self.unreadable += 1,
+            Status::Missing => self.missing += 1,
+        }
+    }
+
+    /// Whether every file was read and matched its checksum, and if `strict` is set, whether every
+    /// line of the manifests was well-formed.
+    pub fn passed(&self, strict: bool) -> bool {
+        self.failed == 0
+            && self.unreadable == 0
+            && self.missing == self.ignored
+            && self.unverified == 0
+            && !(strict && self.malformed > 0)
+    }
+
+    /// Warnings about the lines which were improperly formatted, and the files which couldn't be
+    /// read or didn't match, worded like coreutils'.
+    pub fn warnings(&self) -> Vec<String> {
+        let counts = [
+            (
+                self.malformed,
+                "line is",
+                "lines are",
+                "improperly formatted",
+            ),
+            // like coreutils, files which don't exist couldn't be read either
+            (
+                self.unreadable + self.missing - self.ignored,
+                "listed file",
+                "listed files",
+                "could not be read",
+            ),
+            (
+                self.failed,
+                "computed checksum",
+                "computed checksums",
+                "did NOT match",
+            ),
+        ];
+        counts
+            .into_iter()
+            .filter(|&(count, ..)| count > 0)
+            .map(|(count, one, many, what)| {
+                let noun = if count == 1 { one } else { many };
+                format!("WARNING: {count} {noun} {what}")
+            })
+            .collect()
+    }
+}
+
+/// Quotes a string hashed with `--string`, like `"some text"`. Backslashes, quotes and line breaks
+/// are escaped like paths, and the line must then start with the backslash of the returned
+/// prefix.
+pub fn quote_string(string: &str) -> (&'static str, String) {
+    if !string.contains(['\\', '"', '\n', '\r']) {
+        return ("", format!("\"{string}\""));
+    }
+
+    let escaped = escape_path(string).unwrap_or_else(|| string.to_owned());
+    ("\\", format!("\"{}\"", escaped.replace('"', "\\\"")))
+}
+
+/// Escapes backslashes and line breaks in a path, as coreutils does, returning `None` if there
+/// are none. Lines with an escaped path start with a backslash.
+fn escape_path(path: &str) -> Option<String> {
+    if !path.contains(['\\', '\n', '\r']) {
+        return None;
+    }
+
+    Some(
+        path.replace('\\', "\\\\")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r"),
+    )
+}
+
+/// Reverts [`escape_path`]. Unknown escapes are left as they are.
+fn unescape_path(path: &str) -> String {
+    let mut unescaped = String::with_capacity(path.len());
+    let mut chars = path.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Strips the envelope of PGP clearsigned manifests, as many distributions publish them, from
+/// their lines one at a time. Lines of unsigned manifests are kept as they are. The signature
+/// isn't verified.
+#[derive(Debug, Default)]
+pub struct Clearsign {
+    part: ClearsignPart,
+}
+
+/// Part of a clearsigned manifest a line is in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ClearsignPart {
+    /// Outside of the envelope
+    #[default]
+    Unsigned,
+
+    /// The armor headers, like `Hash: SHA256`, up to a blank line
+    Headers,
+
+    /// The signed manifest, where lines starting with a dash are escaped with `- `
+    Message,
+
+    /// The signature itself
+    Signature,
+}
+
+impl Clearsign {
+    /// Returns the line of the manifest held by `line`, or `None` if it's part of the envelope.
+    pub fn strip<'a>(&mut self, line: &'a str) -> Option<&'a str> {
+        let armor = line.trim_start_matches('\u{feff}').trim_end();
+        match self.part {
+            _ if armor == "-----BEGIN PGP SIGNED MESSAGE-----" => {
+                self.part = ClearsignPart::Headers;
+                None
+            }
+            ClearsignPart::Unsigned => Some(line),
+            ClearsignPart::Headers => {
+                if armor.is_empty() {
+                    self.part = ClearsignPart::Message;
+                }
+                None
+            }
+            ClearsignPart::Message if armor == "-----BEGIN PGP SIGNATURE-----" => {
+                self.part = ClearsignPart::Signature;
+                None
+            }
+            ClearsignPart::Message => Some(line.strip_prefix("- ").unwrap_or(line)),
+            ClearsignPart::Signature => {
+                if armor == "-----END PGP SIGNATURE-----" {
+                    self.part = ClearsignPart::Unsigned;
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Parses a line of a manifest of `checksum` checksums, in either style. Returns `None` for
+/// comments, which start with `#` as in coreutils, and for lines that aren't entries.
+///
+/// Paths run to the end of the line, so they may contain spaces and parentheses: default-style
+/// lines are split after the digest and the mode marker, and BSD-style lines at the last `) = `.
+pub fn parse_line(checksum: Checksum, line: &str) -> Option<Entry<'_>> {
+    // manifests written by some editors start with a byte order mark
+    let line = line.strip_prefix('\u{feff}').unwrap_or(line);
+    if line.starts_with('#') {
+        return None;
+    }
+
+    // a leading backslash marks an escaped path
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    let (expected, path, binary, tag) = match parse_bsd_line(line) {
+        Some((tag, path, digest)) => (digest, path, true, Some(tag.into())),
+        None => {
+            let (digest, path, binary) = parse_default_line(checksum.digest_fields(), line)?;
+            (digest, path, binary, None)
+        }
+    };
+    if path.is_empty() || expected.is_empty() {
+        return None;
+    }
+
+    let path = if escaped {
+        unescape_path(path).into()
+    } else {
+        path.into()
+    };
+    Some(Entry {
+        expected,
+        path,
+        binary,
+        tag,
+    })
+}
+
+/// What the digests of a manifest look like, so that lines with other digests are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigestFormat {
+    /// Encoding of the digests
+    pub encoding: Encoding,
+
+    /// Number of bits of the digests, or `None` if it varies, like when the algorithm of each line
+    /// is detected
+    pub bit_length: Option<usize>,
+}
+
+/// Parses a line like [`parse_line`], but also returns `None` if its digest doesn't have `format`.
+/// The expected checksum of the entry is decoded to lowercase hex. The fields of multi-field
+/// checksums, like `cksum`'s, must be numbers.
+pub fn parse_line_as(checksum: Checksum, format: DigestFormat, line: &str) -> Option<Entry<'_>> {
+    let mut entry = parse_line(checksum, line)?;
+    if checksum.digest_fields() > 1 {
+        let numbers = entry
+            .expected
+            .split(' ')
+            .all(|field| field.bytes().all(|b| b.is_ascii_digit()));
+        return numbers.then_some(entry);
+    }
+
+    let hex = format.encoding.to_hex(&entry.expected)?;
+    if format
+        .bit_length
+        .is_some_and(|bit_length| hex.len() * 4 != bit_length)
+    {
+        return None;
+    }
+    entry.expected = hex;
+    Some(entry)
+}
+
+/// Resolves the path of an entry of the manifest at `manifest`. Like the files the manifest was
+/// generated from, relative paths are relative to `base_dir` if one is given, or else to the
+/// directory of the manifest. Manifests written to another directory with paths relative to the
+/// current one, like with `sha256sum sub/b > sub/SUMS`, are common too, so without `base_dir`, a
+/// path which only exists relative to the current directory is kept as it is.
+pub fn resolve_path(manifest: &Path, base_dir: Option<&Path>, path: &str) -> PathBuf {
+    if let Some(base_dir) = base_dir {
+        return base_dir.join(path);
+    }
+
+    let resolved = manifest.parent().unwrap_or(Path::new("")).join(path);
+    if resolved.exists() || !Path::new(path).exists() {
+        resolved
+    } else {
+        path.into()
+    }
+}
+
+/// Splits a BSD-style line, like `SHA256 (foo.bar) = abcd`, into its tag, path and digest.
+fn parse_bsd_line(line: &str) -> Option<(&str, &str, String)> {
+    let (tag, rest) = line.split_once(" (")?;
+    let (path, digest) = rest.rsplit_once(") = ")?;
+    if tag.is_empty() || tag.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let digest: Vec<&str> = digest.split_whitespace().collect();
+    Some((tag, path, digest.join(" ")))
+}
+
+/// Splits a default-style line, like `abcd *foo.bar`, into the `fields` fields of its digest, its
+/// path and whether it has the binary mode marker. Like in coreutils, single-field digests are
+/// followed by two characters, a space and either a space or the `*` marker.
+fn parse_default_line(fields: usize, line: &str) -> Option<(String, &str, bool)> {
+    if fields == 1 {
+        let line = line.trim_start();
+        let (digest, rest) = line.split_at(line.find(char::is_whitespace)?);
+        let (path, binary) = match rest.strip_prefix("  ") {
+            Some(path) => (path, false),
+            None => (rest.strip_prefix(" *")?, true),
+        };
+        return Some((digest.to_owned(), path, binary));
+    }
+
+    let mut digest = Vec::with_capacity(fields);
+    let mut rest = line;
+    for _ in 0..fields {
+        let field = rest.trim_start();
+        let end = field.find(char::is_whitespace)?;
+        digest.push(&field[..end]);
+        // a single separator, as the path may start with a space
+        rest = &field[end + 1..];
+    }
+
+    // multi-field checksums have no mode marker, but a `*` may still be written before the path
+    let (path, binary) = match rest.strip_prefix('*') {
+        Some(path) => (path, true),
+        None => (rest, false),
+    };
+    Some((digest.join(" "), path, binary))
+}
+
+/// Parses a line of an SFV file, like `foo.bar 1A2B3C4D`: a path followed by its CRC32 checksum.
+/// Returns `None` for comments, which start with `;`, and for lines that aren't entries.
+pub fn parse_sfv_line(line: &str) -> Option<Entry<'_>> {
+    let line = line.trim_end();
+    if line.starts_with(';') {
+        return None;
+    }
+
+    // paths may contain spaces, but the checksum can't
+    let (path, crc) = line.rsplit_once([' ', '\t'])?;
+    let path = path.trim_end();
+    if path.is_empty() || crc.len() != 8 || !crc.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some(Entry {
+        expected: crc.to_ascii_lowercase(),
+        path: path.into(),
+        binary: true,
+        tag: None,
+    })
+}
+
+/// Formats the manifest line for the checksum `digest` of the file at `path`, in `style`. Unless
+/// [`LineStyle::raw_paths`] is set, backslashes and line breaks in the path are escaped as
+/// coreutils does, so that the line can be parsed back.
+pub fn format_line(
+    checksum: Checksum,
+    options: &HashOptions,
+    path: &Path,
+    digest: &str,
+    style: LineStyle,
+) -> String {
+    let path = path.display().to_string();
+    let (prefix, path) = match (!style.raw_paths).then(|| escape_path(&path)).flatten() {
+        Some(escaped) => ("\\", escaped),
+        None => ("", path),
+    };
+
+    if style.bsd {
+        format!(
+            "{prefix}{} ({path}) = {digest}",
+            checksum.full_name(options)
+        )
+    } else if style.binary && checksum.digest_fields() == 1 {
+        format!("{prefix}{digest} *{path}")
+    } else {
+        format!("{prefix}{digest}{}{path}", checksum.separator())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let options = HashOptions {
+            bit_length: 256,
+            ..Default::default()
+        };
+
+        let bsd = LineStyle {
+            bsd: true,
+            ..Default::default()
+        };
+        let binary = LineStyle {
+            binary: true,
+            ..Default::default()
+        };
+        for style in [LineStyle::default(), bsd, binary] {
+            let line = format_line(Checksum::Sha, &options, Path::new("foo.bar"), "abcd", style);
+            let entry = parse_line(Checksum::Sha, &line).unwrap();
+            assert_eq!(entry.path, "foo.bar");
+            assert_eq!(entry.binary, style != LineStyle::default());
+            assert_eq!(entry.tag.as_deref(), style.bsd.then_some("SHA256"));
+            assert!(entry.matches("abcd"));
+        }
+
+        assert_eq!(
+            format_line(Checksum::Sha, &options, Path::new("foo.bar"), "abcd", bsd),
+            "SHA256 (foo.bar) = abcd"
+        );
+        assert_eq!(
+            format_line(
+                Checksum::Sha,
+                &options,
+                Path::new("foo.bar"),
+                "abcd",
+                binary
+            ),
+            "abcd *foo.bar"
+        );
+    }
+
+    #[test]
+    fn test_verification() {
+        let entry = parse_line(Checksum::Md5, "abcd  foo.bar").unwrap();
+        assert_eq!(Verification::new(&entry, "abcd").to_string(), "foo.bar: OK");
+        assert_eq!(
+            Verification::new(&entry, "abce").to_string(),
+            "foo.bar: FAILED"
+        );
+
+        assert!(parse_line(Checksum::Md5, "# abcd  foo.bar").is_none());
+        let bom = parse_line(Checksum::Md5, "\u{feff}abcd  foo.bar").unwrap();
+        assert_eq!(bom.expected, "abcd");
+        let uppercase = parse_line(Checksum::Md5, "ABCD  foo.bar").unwrap();
+        assert!(uppercase.matches("abcd"));
+
+        let mut summary = Summary::default();
+        summary.record(Status::Ok);
+        summary.malformed += 1;
+        assert!(summary.passed(false));
+        assert!(!summary.passed(true));
+        summary.missing += 1;
+        summary.ignored += 1;
+        assert!(summary.passed(false));
+        summary.unverified += 1;
+        assert!(!summary.passed(false));
+        summary.unverified = 0;
+        assert_eq!(
+            summary.warnings(),
+            ["WARNING: 1 line is improperly formatted"]
+        );
+        summary.record(Status::from_name("missing").unwrap());
+        assert!(!summary.passed(false));
+        assert_eq!(
+            summary.warnings()[1],
+            "WARNING: 1 listed file could not be read"
+        );
+        summary.record(Status::Unreadable);
+        assert_eq!(summary.missing, 2);
+        assert_eq!(
+            summary.warnings()[1],
+            "WARNING: 2 listed files could not be read"
+        );
+    }
+
+    #[test]
+    fn test_quote_string() {
+        assert_eq!(quote_string("some text"), ("", r#""some text""#.to_owned()));
+        assert_eq!(
+            quote_string("say \"hi\"\\\n"),
+            ("\\", r#""say \"hi\"\\\n""#.to_owned())
+        );
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        let manifest = Path::new("dir/SUMS");
+        let base_dir = Path::new("base");
+        assert_eq!(
+            resolve_path(manifest, Some(base_dir), "a"),
+            base_dir.join("a")
+        );
+        assert_eq!(resolve_path(manifest, None, "a"), Path::new("dir/a"));
+        assert_eq!(resolve_path(Path::new("-"), None, "a"), Path::new("a"));
+        // the tests run in the directory of the package
+        assert_eq!(
+            resolve_path(manifest, None, "Cargo.toml"),
+            Path::new("Cargo.toml")
+        );
+    }
+
+    #[test]
+    fn test_escaped_paths() {
+        let options = HashOptions {
+            bit_length: 128,
+            ..Default::default()
+        };
+        let path = Path::new("a\\b\nc");
+
+        for bsd in [false, true] {
+            let style = LineStyle {
+                bsd,
+                ..Default::default()
+            };
+            let line = format_line(Checksum::Md5, &options, path, "abcd", style);
+            assert!(line.starts_with('\\') && !line.contains('\n'), "{line}");
+            let entry = parse_line(Checksum::Md5, &line).unwrap();
+            assert_eq!(entry.path, "a\\b\nc");
+
+            let verification = Verification::new(&entry, "abcd");
+            assert_eq!(verification.to_string(), "\\a\\\\b\\nc: OK");
+        }
+
+        let entry = parse_line(Checksum::Md5, "\\abcd  a\\\\b").unwrap();
+        assert_eq!(Verification::new(&entry, "abcd").to_string(), "a\\b: OK");
+
+        let style = LineStyle {
+            raw_paths: true,
+            ..Default::default()
+        };
+        let line = format_line(Checksum::Md5, &options, path, "abcd", style);
+        assert_eq!(line, "abcd  a\\b\nc");
+    }
+
+    #[test]
+    fn test_clearsign() {
+        let signed = "-----BEGIN PGP SIGNED MESSAGE-----\nHash: SHA256\n\nabcd  foo.bar\n- -dash\n\
+            -----BEGIN PGP SIGNATURE-----\n\niQIzBAEBCAAdFiEE\n-----END PGP SIGNATURE-----\n";
+        let mut clearsign = Clearsign::default();
+        let lines: Vec<&str> = signed
+            .lines()
+            .filter_map(|line| clearsign.strip(line))
+            .collect();
+        assert_eq!(lines, ["abcd  foo.bar", "-dash"]);
+
+        let mut clearsign = Clearsign::default();
+        assert_eq!(clearsign.strip("- abcd"), Some("- abcd"));
+    }
+
+    #[test]
+    fn test_sfv() {
+        let entry = parse_sfv_line("my file.bar 1A2B3C4D").unwrap();
+        assert_eq!(entry.path, "my file.bar");
+        assert!(entry.matches("1a2b3c4d"));
+        assert!(parse_sfv_line("; generated by steadyhash").is_none());
+        assert!(parse_sfv_line("foo.bar 1A2B3C4").is_none());
+        assert!(parse_sfv_line("1A2B3C4D").is_none());
+    }
+
+    #[test]
+    fn test_multi_field_checksums() {
+        let entry = parse_line(Checksum::BsdSum, "00123     5 foo.bar").unwrap();
+        assert_eq!(entry.path, "foo.bar");
+        assert!(entry.matches("00123     5"));
+        assert!(parse_line(Checksum::BsdSum, "00123 foo.bar").is_none());
+    }
+
+    #[test]
+    fn test_paths_with_spaces() {
+        let entry = parse_line(Checksum::Md5, "abcd  my file.txt").unwrap();
+        assert_eq!((&*entry.path, entry.binary), ("my file.txt", false));
+        let entry = parse_line(Checksum::Md5, "abcd * leading space").unwrap();
+        assert_eq!((&*entry.path, entry.binary), (" leading space", true));
+
+        let entry = parse_line(Checksum::Md5, "MD5 (dir (copy)/f) = x) = abcd").unwrap();
+        assert_eq!(entry.path, "dir (copy)/f) = x");
+        assert_eq!(entry.tag.as_deref(), Some("MD5"));
+        assert!(entry.matches("abcd"));
+
+        // default-style paths may look like BSD-style lines
+        let entry = parse_line(Checksum::Md5, "abcd  (a) = b").unwrap();
+        assert_eq!((&*entry.path, entry.tag), ("(a) = b", None));
+
+        assert!(parse_line(Checksum::Md5, "abcd").is_none());
+        assert!(parse_line(Checksum::Md5, "abcd  ").is_none());
+        assert!(parse_line(Checksum::Md5, "MD5 (foo.bar) = ").is_none());
+    }
+
+    #[test]
+    fn test_malformed_lines() {
+        // the digest must be followed by two spaces or a space and a `*`
+        assert!(parse_line(Checksum::Md5, "garbage line").is_none());
+        assert!(parse_line(Checksum::Md5, "abcd\tfoo.bar").is_none());
+
+        let md5 = DigestFormat {
+            encoding: Encoding::Hex,
+            bit_length: Some(128),
+        };
+        let digest = "d41d8cd98f00b204e9800998ecf8427e";
+        let line = format!("0X{}  e", digest.to_uppercase());
+        assert_eq!(
+            parse_line_as(Checksum::Md5, md5, &line).unwrap().expected,
+            digest
+        );
+        assert!(parse_line_as(Checksum::Md5, md5, "garbage  line").is_none());
+        assert!(parse_line_as(Checksum::Md5, md5, &format!("{digest}00  e")).is_none());
+        assert!(parse_line_as(Checksum::Md5, md5, &format!("{}  e", &digest[2..])).is_none());
+
+        // without a bit length, any length goes
+        let detected = DigestFormat {
+            bit_length: None,
+            ..md5
+        };
+        assert!(parse_line_as(Checksum::Md5, detected, "abcd  e").is_some());
+        assert!(parse_line_as(Checksum::BsdSum, detected, "00123 5 e").is_some());
+        assert!(parse_line_as(Checksum::BsdSum, detected, "0x123 5 e").is_none());
+    }
+}
@@ -0,0 +1,26 @@
+//! The `manpage` subcommand, which prints a man page generated from the command-line definition.
+
+use crate::Args;
+use clap::CommandFactory;
+use clap_mangen::Man;
+use std::io::{self, Write};
+
+/// Writes the man page of steadyhash in roff format to `out`.
+pub fn render(out: &mut dyn Write) -> io::Result<()> {
+    Man::new(Args::command()).render(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut page = Vec::new();
+        render(&mut page).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.starts_with(".ie"));
+        assert!(page.contains(".TH steadyhash 1"));
+        assert!(page.contains("\\-\\-type"));
+    }
+}
@@ -0,0 +1,189 @@
+//! Multi-call behavior: when run through a link named after a coreutils tool, like `sha256sum`
+//! or `b2sum`, steadyhash uses that tool's algorithm and accepts its options instead of its own.
+
+use crate::Args;
+use clap::Parser;
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+/// A tool steadyhash can stand in for.
+pub struct Program {
+    /// Name of the tool, which the link must have
+    pub name: &'static str,
+
+    /// Algorithm of the tool, as given to `--type`
+    pub checksum_type: &'static str,
+
+    /// Bit length used when `--length` isn't given, if the type doesn't set one
+    pub default_bits: Option<usize>,
+}
+
+const fn program(name: &'static str, checksum_type: &'static str) -> Program {
+    Program {
+        name,
+        checksum_type,
+        default_bits: None,
+    }
+}
+
+pub const PROGRAMS: &[Program] = &[
+    program("md5sum", "md5"),
+    program("sha1sum", "sha1"),
+    program("sha224sum", "sha224"),
+    program("sha256sum", "sha256"),
+    program("sha384sum", "sha384"),
+    program("sha512sum", "sha512"),
+    program("sha3sum", "sha3"),
+    // unlike the default of steadyhash, b2sum computes BLAKE2b-512 checksums
+    Program {
+        name: "b2sum",
+        checksum_type: "blake2b",
+        default_bits: Some(512),
+    },
+];
+
+/// Finds the tool steadyhash was invoked as, from the first argument of its command line.
+pub fn invoked_as(argv0: &OsStr) -> Option<&'static Program> {
+    // ignore the directory and the extension of executables, as in `/usr/bin/sha256sum.exe`
+    let name = Path::new(argv0).file_stem()?.to_str()?;
+    PROGRAMS.iter().find(|program| program.name == name)
+}
+
+/// The options of the coreutils checksum tools.
+#[derive(Parser)]
+#[clap(
+    version,
+    about = "Print or check checksums, as a drop-in replacement for the coreutils tools"
+)]
+pub struct CompatArgs {
+    #[clap(short, long, help = "read checksums from the FILEs and check them")]
+    check: bool,
+
+    #[clap(long, help = "create a BSD-style checksum")]
+    tag: bool,
+
+    #[clap(short, long, help = "read in binary mode")]
+    binary: bool,
+
+    #[clap(
+        short,
+        long,
+        conflicts_with = "binary",
+        help = "read in text mode, with CRLF line endings read as LF (the default on Windows)"
+    )]
+    text: bool,
+
+    #[clap(
+        short = 'l',
+        long = "length",
+        help = "the bit length of the checksum (b2sum, sha3sum)"
+    )]
+    bit_length: Option<usize>,
+
+    #[clap(
+        short,
+        long,
+        help = "end each output line with NUL, not newline, and disable file name escaping"
+    )]
+    zero: bool,
+
+    #[clap(
+        long,
+        help = "don't fail or report status for missing files (check mode only)"
+    )]
+    ignore_missing: bool,
+
+    #[clap(
+        long,
+        help = "don't print OK for each successfully verified file (check mode only)"
+    )]
+    quiet: bool,
+
+    #[clap(
+        long,
+        help = "don't output anything, status code shows success (check mode only)"
+    )]
+    status: bool,
+
+    #[clap(
+        long,
+        help = "exit non-zero for improperly formatted checksum lines (check mode only)"
+    )]
+    strict: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "warn about improperly formatted checksum lines (check mode only)"
+    )]
+    warn: bool,
+
+    #[clap(
+        name = "FILEs",
+        help = "the files to process, or standard input if there are none"
+    )]
+    file_path: Vec<PathBuf>,
+}
+
+impl CompatArgs {
+    /// Converts the options into those of steadyhash, using the algorithm of `program`.
+    pub fn into_args(self, program: &Program) -> Args {
+        let mut args = Args::parse_from(["steadyhash"]);
+        args.check = self.check;
+        args.verify.quiet = self.quiet;
+        args.verify.status = self.status;
+        args.verify.strict = self.strict;
+        args.verify.warn = self.warn;
+        args.verify.ignore_missing = self.ignore_missing;
+
+        let hash = &mut args.hash;
+        hash.algorithm.checksum_type = vec![program.checksum_type.into()];
+        hash.algorithm.bit_length = self.bit_length.or(program.default_bits);
+        hash.bsd = self.tag;
+        hash.binary = self.binary;
+        hash.input.text = self.text;
+        hash.zero = self.zero;
+        if self.file_path.is_empty() && !self.check {
+            hash.stdin = true;
+        } else {
+            hash.file_path = self.file_path;
+        }
+
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoked_as() {
+        let program = invoked_as(OsStr::new("/usr/bin/sha256sum")).unwrap();
+        assert_eq!(program.checksum_type, "sha256");
+        assert_eq!(invoked_as(OsStr::new("b2sum.exe")).unwrap().name, "b2sum");
+        assert!(invoked_as(OsStr::new("steadyhash")).is_none());
+        assert!(invoked_as(OsStr::new("sha256sum-old")).is_none());
+    }
+
+    #[test]
+    fn test_into_args() {
+        let args = CompatArgs::parse_from(["b2sum", "--tag", "-l", "256", "file"])
+            .into_args(invoked_as(OsStr::new("b2sum")).unwrap());
+        assert!(args.hash.bsd);
+        assert_eq!(args.hash.algorithm.bit_length, Some(256));
+        assert_eq!(args.hash.file_path, [PathBuf::from("file")]);
+
+        let args = CompatArgs::parse_from(["md5sum"]).into_args(&PROGRAMS[0]);
+        assert!(args.hash.stdin);
+        assert_eq!(args.hash.algorithm.checksum_type, ["md5"]);
+
+        let args = CompatArgs::parse_from(["sha256sum", "-c", "--status", "-w", "-z", "SUMS"])
+            .into_args(&PROGRAMS[3]);
+        assert!(args.check && args.verify.status && args.verify.warn);
+        assert!(!args.verify.quiet && !args.verify.strict);
+        assert!(args.hash.zero);
+    }
+}
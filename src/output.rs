@@ -0,0 +1,592 @@
+//! Formatting and printing the results of a run: the checksums computed by `hash`, besides the
+//! default and BSD-style lines, and the results of `check`.
+
+use crate::{color, json};
+use clap::ValueEnum;
+use std::{
+    borrow::Cow,
+    fmt,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use steadyhash::{
+    errors::TemplateError,
+    manifest::{Summary, Verification},
+};
+
+/// How the results of a run are printed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Lines in the default or BSD style, or as given by `--format`
+    #[default]
+    Text,
+
+    /// A JSON array, with an object per checksum or result
+    Json,
+
+    /// JSON Lines, with an object per checksum or result, printed as soon as it's computed
+    Jsonl,
+
+    /// Comma-separated values, with a header row
+    Csv,
+
+    /// Tab-separated values, with a header row
+    Tsv,
+
+    /// A Simple File Verification (SFV) file of CRC32 checksums
+    Sfv,
+}
+
+impl OutputFormat {
+    /// What goes between the outputs of two checksums or results.
+    pub fn separator(self) -> &'static str {
+        match self {
+            OutputFormat::Json => ",\n",
+            _ => "\n",
+        }
+    }
+
+    /// Delimiter of the fields, in the CSV and TSV formats.
+    pub fn delimiter(self) -> Option<char> {
+        match self {
+            OutputFormat::Csv => Some(','),
+            OutputFormat::Tsv => Some('\t'),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.to_possible_value().expect("no value is skipped");
+        f.write_str(value.get_name())
+    }
+}
+
+/// Where the output of a run is written.
+pub enum Destination {
+    Stdout,
+    File(AtomicFile),
+
+    /// Nowhere, when only the exit status matters
+    Sink,
+}
+
+impl Write for Destination {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Destination::Stdout => io::stdout().write(buf),
+            Destination::File(file) => file.write(buf),
+            Destination::Sink => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Destination::Stdout => io::stdout().flush(),
+            Destination::File(file) => file.flush(),
+            Destination::Sink => Ok(()),
+        }
+    }
+}
+
+/// A file which is written under a temporary name in the same directory, and renamed once
+/// complete, so that an interrupted run never leaves a partial manifest behind.
+pub struct AtomicFile {
+    path: PathBuf,
+    temp_path: PathBuf,
+
+    /// The temporary file, until it's renamed
+    writer: Option<BufWriter<File>>,
+}
+
+impl AtomicFile {
+    pub fn create(path: PathBuf) -> io::Result<AtomicFile> {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = path.with_file_name(format!(".{name}.{}.tmp", process::id()));
+        let writer = BufWriter::new(File::create(&temp_path)?);
+
+        Ok(AtomicFile {
+            path,
+            temp_path,
+            writer: Some(writer),
+        })
+    }
+
+    /// Renames the file to its final path, replacing any file there.
+    pub fn commit(mut self) -> io::Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.into_inner()?.sync_all()?;
+        }
+        fs::rename(&self.temp_path, &self.path)
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.as_mut().expect("not committed").write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.as_mut().expect("not committed").flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        // the run failed before the file was complete
+        if self.writer.take().is_some() {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Prints the output of each file, with what the output format needs around it.
+pub struct Printer {
+    out: Destination,
+
+    format: OutputFormat,
+
+    /// Names of the fields, in the CSV and TSV formats
+    header: &'static [&'static str],
+
+    /// Whether to color the status of check results
+    color: bool,
+
+    /// What ends the lines of the text format
+    terminator: char,
+
+    /// Whether anything was printed yet
+    printed: bool,
+}
+
+impl Printer {
+    /// Creates a printer for the checksums computed by `hash`, or for the results of `check` if
+    /// `check` is set. Lines of the text format end with `terminator`.
+    pub fn new(
+        out: Destination,
+        format: OutputFormat,
+        check: bool,
+        color: bool,
+        terminator: char,
+    ) -> Printer {
+        Printer {
+            out,
+            format,
+            header: if check {
+                &["path", "algorithm", "status"]
+            } else {
+                &["path", "algorithm", "digest", "size", "mtime"]
+            },
+            color,
+            terminator,
+            printed: false,
+        }
+    }
+
+    /// Prints the output of a file, as lines or as JSON objects separated by commas.
+    pub fn print(&mut self, output: &str) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Text if self.color => writeln!(self.out, "{}", color::paint(output))?,
+            OutputFormat::Text => write!(self.out, "{output}{}", self.terminator)?,
+            OutputFormat::Json => {
+                let before = if self.printed { ",\n" } else { "[\n" };
+                write!(self.out, "{before}{output}")?;
+            }
+            _ => {
+                if !self.printed {
+                    self.print_header()?;
+                }
+                writeln!(self.out, "{output}")?;
+            }
+        }
+        self.printed = true;
+        Ok(())
+    }
+
+    /// Ends the output, closing the JSON array, and renames the output file into place. Empty CSV,
+    /// TSV and SFV outputs still get a header.
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.format {
+            OutputFormat::Json if self.printed => writeln!(self.out, "\n]")?,
+            OutputFormat::Json => writeln!(self.out, "[]")?,
+            _ if !self.printed => self.print_header()?,
+            _ => {}
+        }
+
+        match self.out {
+            Destination::Stdout => io::stdout().flush(),
+            Destination::File(file) => file.commit(),
+            Destination::Sink => Ok(()),
+        }
+    }
+
+    fn print_header(&mut self) -> io::Result<()> {
+        if let Some(delimiter) = self.format.delimiter() {
+            writeln!(self.out, "{}", self.header.join(&delimiter.to_string()))?;
+        } else if self.format == OutputFormat::Sfv {
+            let version = env!("CARGO_PKG_VERSION");
+            writeln!(self.out, "; Generated by steadyhash {version}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A checksum computed for a file, with what can be printed along with it.
+pub struct Record<'a> {
+    /// Path of the file, as given on the command line
+    pub path: &'a Path,
+
+    /// Name of the algorithm, as in BSD-style lines
+    pub algorithm: &'a str,
+
+    /// Bit length of the checksum
+    pub bit_length: usize,
+
+    /// The checksum, as written with the output options
+    pub checksum: &'a str,
+
+    /// Size of the file, in bytes
+    pub size: u64,
+
+    /// Last modification time of the file, if it's known
+    pub mtime: Option<SystemTime>,
+}
+
+impl Record<'_> {
+    /// Formats the record as a JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"path":{},"algorithm":{},"length":{},"digest":{},"size":{}}}"#,
+            json::string(&self.path.display().to_string()),
+            json::string(self.algorithm),
+            self.bit_length,
+            json::string(self.checksum),
+            self.size,
+        )
+    }
+
+    /// Formats the record as a row of fields separated by `delimiter`.
+    pub fn to_csv(&self, delimiter: char) -> String {
+        let size = self.size.to_string();
+        let mtime = self.mtime.map(format_time).unwrap_or_default();
+        let path = self.path.display().to_string();
+        let fields = [&path, self.algorithm, self.checksum, &size, &mtime];
+        join_fields(&fields, delimiter)
+    }
+
+    /// Formats the record as a line of an SFV file, with the checksum in uppercase.
+    pub fn to_sfv(&self) -> String {
+        format!(
+            "{} {}",
+            self.path.display(),
+            self.checksum.to_ascii_uppercase()
+        )
+    }
+}
+
+/// Formats the result of checking a file, as `foo.bar: OK` in the text format.
+pub fn format_verification(
+    format: OutputFormat,
+    verification: &Verification,
+    algorithm: &str,
+) -> String {
+    let status = verification.status.name();
+    match format.delimiter() {
+        Some(delimiter) => join_fields(&[&verification.path, algorithm, status], delimiter),
+        None if format == OutputFormat::Text => verification.to_string(),
+        None => format!(
+            r#"{{"path":{},"algorithm":{},"status":"{status}"}}"#,
+            json::string(&verification.path),
+            json::string(algorithm),
+        ),
+    }
+}
+
+/// Formats the counts of the results of check mode, printed after them in the JSON formats only,
+/// as CSV and TSV rows all have the same fields.
+pub fn format_summary(format: OutputFormat, summary: &Summary) -> Option<String> {
+    matches!(format, OutputFormat::Json | OutputFormat::Jsonl)
+        .then(|| format!(r#"{{"summary":{}}}"#, summary_json(summary)))
+}
+
+/// Formats the counts of the results of check mode as a JSON object.
+pub fn summary_json(summary: &Summary) -> String {
+    format!(
+        r#"{{"ok":{},"failed":{},"unreadable":{},"missing":{},"malformed":{}}}"#,
+        summary.ok, summary.failed, summary.unreadable, summary.missing, summary.malformed
+    )
+}
+
+/// Joins fields with `delimiter`, quoting those containing it, quotes or line breaks.
+fn join_fields(fields: &[&str], delimiter: char) -> String {
+    let fields: Vec<Cow<str>> = fields
+        .iter()
+        .map(|&field| {
+            if field.contains([delimiter, '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\"")).into()
+            } else {
+                field.into()
+            }
+        })
+        .collect();
+    fields.join(&delimiter.to_string())
+}
+
+/// A template given to `--format`, like `{hash}  {file}`, rendered once per file and algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    /// The template as it was given
+    source: String,
+
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Hash,
+    UppercaseHash,
+    File,
+    Size,
+    Algorithm,
+    Mtime,
+}
+
+impl Template {
+    /// Whether the template shows the modification time, which has to be looked up separately.
+    pub fn uses_mtime(&self) -> bool {
+        self.parts.contains(&Part::Mtime)
+    }
+
+    /// Fills in the placeholders with the fields of `record`.
+    pub fn render(&self, record: &Record) -> String {
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => rendered.push_str(literal),
+                Part::Hash => rendered.push_str(record.checksum),
+                Part::UppercaseHash => rendered.push_str(&record.checksum.to_ascii_uppercase()),
+                Part::File => rendered.push_str(&record.path.display().to_string()),
+                Part::Size => rendered.push_str(&record.size.to_string()),
+                Part::Algorithm => rendered.push_str(record.algorithm),
+                Part::Mtime => {
+                    rendered.push_str(&record.mtime.map(format_time).unwrap_or_default())
+                }
+            }
+        }
+
+        rendered
+    }
+}
+
+/// Parses a template, where `{{` and `}}` stand for braces and `\t`, `\n` and `\\` are escapes.
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => literal.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => literal.push('}'),
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(TemplateError::UnmatchedBrace('{')),
+                        }
+                    }
+                    let part = match name.as_str() {
+                        "hash" => Part::Hash,
+                        "HASH" => Part::UppercaseHash,
+                        "file" => Part::File,
+                        "size" => Part::Size,
+                        "algo" => Part::Algorithm,
+                        "mtime" => Part::Mtime,
+                        _ => return Err(TemplateError::UnknownPlaceholder(name)),
+                    };
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(part);
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace('}')),
+                '\\' => match chars.next_if(|c| matches!(c, 't' | 'n' | '\\')) {
+                    Some('t') => literal.push('\t'),
+                    Some('n') => literal.push('\n'),
+                    _ => literal.push('\\'),
+                },
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Template {
+            source: s.to_owned(),
+            parts,
+        })
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Formats a time as an RFC 3339 timestamp in UTC, like `2024-03-17T09:30:00Z`.
+pub fn format_time(time: SystemTime) -> String {
+    // times before 1970 are clamped, as files don't have them in practice
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // civil_from_days, from Howard Hinnant's date algorithms
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use steadyhash::manifest::Status;
+
+    #[test]
+    fn test_render() {
+        let template: Template = "{algo}:{HASH}\\t{{{size}}} {file}\\n".parse().unwrap();
+        let record = Record {
+            path: Path::new("foo.bar"),
+            algorithm: "MD5",
+            bit_length: 128,
+            checksum: "abcd",
+            size: 42,
+            mtime: None,
+        };
+        assert_eq!(template.render(&record), "MD5:ABCD\t{42} foo.bar\n");
+        assert!(!template.uses_mtime());
+
+        assert!("{digest}".parse::<Template>().is_err());
+        assert!("{hash".parse::<Template>().is_err());
+        assert!("hash}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let record = Record {
+            path: Path::new("a \"b\""),
+            algorithm: "SHA256",
+            bit_length: 256,
+            checksum: "abcd",
+            size: 3,
+            mtime: None,
+        };
+        assert_eq!(
+            record.to_json(),
+            r#"{"path":"a \"b\"","algorithm":"SHA256","length":256,"digest":"abcd","size":3}"#
+        );
+
+        let verification = Verification {
+            path: "foo.bar".into(),
+            status: Status::Failed,
+        };
+        assert_eq!(
+            format_verification(OutputFormat::Jsonl, &verification, "MD5"),
+            r#"{"path":"foo.bar","algorithm":"MD5","status":"failed"}"#
+        );
+
+        let summary = Summary {
+            ok: 2,
+            failed: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_summary(OutputFormat::Json, &summary).unwrap(),
+            r#"{"summary":{"ok":2,"failed":1,"unreadable":0,"missing":0,"malformed":0}}"#
+        );
+        assert!(format_summary(OutputFormat::Csv, &summary).is_none());
+    }
+
+    #[test]
+    fn test_atomic_file() {
+        let path = std::env::temp_dir().join(format!("steadyhash-output-{}", process::id()));
+
+        let mut file = AtomicFile::create(path.clone()).unwrap();
+        file.write_all(b"partial").unwrap();
+        drop(file);
+        assert!(!path.exists());
+
+        let mut file = AtomicFile::create(path.clone()).unwrap();
+        file.write_all(b"complete").unwrap();
+        assert!(!path.exists());
+        file.commit().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"complete");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_csv() {
+        let record = Record {
+            path: Path::new("a, \"b\""),
+            algorithm: "SHA256",
+            bit_length: 256,
+            checksum: "abcd",
+            size: 3,
+            mtime: Some(UNIX_EPOCH),
+        };
+        assert_eq!(
+            record.to_csv(','),
+            r#""a, ""b""",SHA256,abcd,3,1970-01-01T00:00:00Z"#
+        );
+        assert_eq!(
+            record.to_csv('\t'),
+            "\"a, \"\"b\"\"\"\tSHA256\tabcd\t3\t1970-01-01T00:00:00Z"
+        );
+
+        let verification = Verification {
+            path: "foo,bar".into(),
+            status: Status::Ok,
+        };
+        assert_eq!(
+            format_verification(OutputFormat::Csv, &verification, "MD5"),
+            "\"foo,bar\",MD5,ok"
+        );
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        let time = UNIX_EPOCH + Duration::from_secs(1_710_667_800);
+        assert_eq!(format_time(time), "2024-03-17T09:30:00Z");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400);
+        assert_eq!(format_time(leap_day), "2000-02-29T00:00:00Z");
+    }
+}
@@ -0,0 +1,127 @@
+//! Running work on several threads while keeping the output in order.
+
+use std::{
+    collections::BTreeMap,
+    num::NonZero,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// Resolves a `--jobs` value, where 0 means one job per CPU.
+pub fn resolve_jobs(jobs: usize) -> usize {
+    match jobs {
+        0 => thread::available_parallelism().map_or(1, NonZero::get),
+        jobs => jobs,
+    }
+}
+
+/// Runs `work` on every item using up to `jobs` threads, and passes the results to `output` in the
+/// same order as `items`, as soon as they're available.
+///
+/// Items are handed out in order, so only the results of items that finished early have to be
+/// kept around. Once `output` fails, no more items are started and the error is returned.
+pub fn for_each_ordered<T, R, E>(
+    items: &[T],
+    jobs: usize,
+    work: impl Fn(&T) -> R + Sync,
+    mut output: impl FnMut(&T, R) -> Result<(), E>,
+) -> Result<(), E>
+where
+    T: Sync,
+    R: Send,
+{
+    let jobs = resolve_jobs(jobs).min(items.len());
+    if jobs <= 1 {
+        return items.iter().try_for_each(|item| output(item, work(item)));
+    }
+
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let tx = tx.clone();
+            let (next, stop, work) = (&next, &stop, &work);
+
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= items.len() || tx.send((i, work(&items[i]))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+
+        for (i, result) in rx {
+            pending.insert(i, result);
+
+            while let Some(result) = pending.remove(&expected) {
+                if let Err(e) = output(&items[expected], result) {
+                    stop.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+                expected += 1;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_output_is_ordered() {
+        let items: Vec<u64> = (0..50).collect();
+        let mut seen = Vec::new();
+
+        for_each_ordered(
+            &items,
+            4,
+            |&i| {
+                // make early items finish last
+                thread::sleep(Duration::from_micros(50 - i));
+                i * 2
+            },
+            |&i, doubled| {
+                assert_eq!(doubled, i * 2);
+                seen.push(i);
+                Ok::<_, ()>(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(seen, items);
+    }
+
+    #[test]
+    fn test_stops_on_error() {
+        let items: Vec<u32> = (0..1000).collect();
+        let done = AtomicUsize::new(0);
+
+        let result = for_each_ordered(
+            &items,
+            2,
+            |_| {
+                thread::sleep(Duration::from_micros(100));
+                done.fetch_add(1, Ordering::Relaxed);
+            },
+            |&i, ()| if i == 3 { Err(i) } else { Ok(()) },
+        );
+
+        assert_eq!(result, Err(3));
+        assert!(done.load(Ordering::Relaxed) < items.len());
+    }
+}
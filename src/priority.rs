@@ -0,0 +1,75 @@
+//! Lowering the I/O priority of the process, for `--nice-io`.
+
+use std::io;
+
+/// Asks the OS to only serve the disk reads of this process when the disk isn't otherwise busy,
+/// so that background scans don't slow down interactive workloads. It should be called before
+/// spawning any threads, which inherit the priority on Linux.
+#[cfg(target_os = "linux")]
+pub fn set_idle_io_priority() -> io::Result<()> {
+    // from linux/ioprio.h, which libc doesn't expose
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+    // SAFETY: ioprio_set only takes integers
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+        )
+    };
+
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_idle_io_priority() -> io::Result<()> {
+    use std::ffi::c_int;
+
+    // from sys/resource.h
+    const IOPOL_TYPE_DISK: c_int = 0;
+    const IOPOL_SCOPE_PROCESS: c_int = 0;
+    const IOPOL_THROTTLE: c_int = 3;
+
+    unsafe extern "C" {
+        fn setiopolicy_np(iotype: c_int, scope: c_int, policy: c_int) -> c_int;
+    }
+
+    // SAFETY: setiopolicy_np only takes integers
+    let ret = unsafe { setiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_PROCESS, IOPOL_THROTTLE) };
+
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// On Windows, background mode lowers the CPU and memory priority of the process as well.
+#[cfg(windows)]
+pub fn set_idle_io_priority() -> io::Result<()> {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, PROCESS_MODE_BACKGROUND_BEGIN, SetPriorityClass,
+    };
+
+    // SAFETY: the pseudo handle returned by GetCurrentProcess is always valid
+    let ret = unsafe { SetPriorityClass(GetCurrentProcess(), PROCESS_MODE_BACKGROUND_BEGIN) };
+
+    if ret == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn set_idle_io_priority() -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
@@ -0,0 +1,264 @@
+//! Progress bars drawn on stderr while hashing, and counting the bytes that were hashed.
+
+use crate::units::format_size;
+use clap::ValueEnum;
+use std::{
+    io::{self, IsTerminal, Read, Write},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// With `--progress=auto`, runs hashing fewer bytes than this don't get a progress bar, as they're
+/// done before one would be useful.
+pub const PROGRESS_THRESHOLD: u64 = 128 * 1024 * 1024;
+
+/// Minimum time between two redraws of a progress bar.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Width of the bar itself, in characters.
+const BAR_WIDTH: usize = 30;
+
+/// When to show progress bars.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressMode {
+    /// Only for large inputs, when stderr is a terminal
+    #[default]
+    Auto,
+
+    /// Always, even when stderr is redirected
+    Always,
+
+    /// Never
+    Never,
+}
+
+impl ProgressMode {
+    /// Whether a progress bar should be shown for a run hashing `size` bytes in total.
+    pub fn enabled(self, size: u64) -> bool {
+        match self {
+            ProgressMode::Auto => size >= PROGRESS_THRESHOLD && io::stderr().is_terminal(),
+            ProgressMode::Always => true,
+            ProgressMode::Never => false,
+        }
+    }
+}
+
+/// A progress bar showing the bytes hashed so far, the rate and the ETA, either for a single file
+/// or for all the files of a run. It can be advanced from several threads at once.
+pub struct ProgressBar {
+    /// Name of the file, if the bar is for a single file
+    label: Option<String>,
+
+    total_bytes: u64,
+    total_files: usize,
+    done_bytes: AtomicU64,
+    done_files: AtomicUsize,
+    start: Instant,
+    state: Mutex<DrawState>,
+}
+
+#[derive(Default)]
+struct DrawState {
+    last_draw: Option<Instant>,
+
+    /// Whether the bar is currently on the screen
+    visible: bool,
+}
+
+impl ProgressBar {
+    /// Creates a bar for a single file of `size` bytes.
+    pub fn for_file(label: String, size: u64) -> ProgressBar {
+        ProgressBar::new(Some(label), size, 1)
+    }
+
+    /// Creates a bar for `count` files, totalling `size` bytes.
+    pub fn for_files(count: usize, size: u64) -> ProgressBar {
+        ProgressBar::new(None, size, count)
+    }
+
+    fn new(label: Option<String>, total_bytes: u64, total_files: usize) -> ProgressBar {
+        ProgressBar {
+            label,
+            total_bytes,
+            total_files,
+            done_bytes: AtomicU64::new(0),
+            done_files: AtomicUsize::new(0),
+            start: Instant::now(),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Records that `count` more bytes have been hashed, redrawing the bar if it's been a while.
+    /// The bar is first drawn after one interval, so that it doesn't flash for fast runs.
+    pub fn advance(&self, count: u64) {
+        self.done_bytes.fetch_add(count, Ordering::Relaxed);
+
+        let now = Instant::now();
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if now.duration_since(state.last_draw.unwrap_or(self.start)) >= REDRAW_INTERVAL {
+            state.last_draw = Some(now);
+            state.visible = true;
+            self.draw(now);
+        }
+    }
+
+    /// Records that a file has been hashed.
+    pub fn file_done(&self) {
+        self.done_files.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Erases the bar while `f` runs, so that it can print to the terminal, and then draws it again.
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        let Ok(state) = self.state.lock() else {
+            return f();
+        };
+
+        if state.visible {
+            clear_line();
+        }
+        let result = f();
+        if state.visible {
+            self.draw(Instant::now());
+        }
+
+        result
+    }
+
+    fn draw(&self, now: Instant) {
+        let done = self.done_bytes.load(Ordering::Relaxed);
+        let fraction = if self.total_bytes == 0 {
+            1.0
+        } else {
+            (done as f64 / self.total_bytes as f64).min(1.0)
+        };
+        let filled = (fraction * BAR_WIDTH as f64) as usize;
+
+        let elapsed = now.duration_since(self.start).as_secs_f64();
+        let rate = if elapsed > 0.0 {
+            done as f64 / elapsed
+        } else {
+            0.0
+        };
+        let eta = if rate > 0.0 {
+            format_duration(self.total_bytes.saturating_sub(done) as f64 / rate)
+        } else {
+            "--:--".to_owned()
+        };
+
+        let label = match &self.label {
+            Some(label) => label.clone(),
+            None => format!(
+                "{}/{} files",
+                self.done_files.load(Ordering::Relaxed),
+                self.total_files
+            ),
+        };
+
+        // errors writing to the terminal aren't worth failing the run over
+        let _ = write!(
+            io::stderr(),
+            "\r\x1b[K{label} [{}{}] {:>3}% {}/{} {}/s ETA {eta}",
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            (fraction * 100.0) as u32,
+            format_size(done),
+            format_size(self.total_bytes),
+            format_size(rate as u64),
+        );
+    }
+
+    /// Erases the bar, so that the checksum can be printed in its place.
+    pub fn finish(self) {
+        if self.state.lock().is_ok_and(|state| state.visible) {
+            clear_line();
+        }
+    }
+}
+
+fn clear_line() {
+    let _ = write!(io::stderr(), "\r\x1b[K");
+}
+
+/// Formats a number of seconds as `m:ss`, or `h:mm:ss` past an hour.
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// A reader that counts the bytes read through it, and advances a progress bar if there's one.
+pub struct CountingReader<'a, R> {
+    inner: R,
+    count: u64,
+    bar: Option<&'a ProgressBar>,
+}
+
+impl<'a, R: Read> CountingReader<'a, R> {
+    pub fn new(inner: R, bar: Option<&'a ProgressBar>) -> CountingReader<'a, R> {
+        CountingReader {
+            inner,
+            count: 0,
+            bar,
+        }
+    }
+
+    /// Number of bytes read so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl<R: Read> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.count += count as u64;
+        if let Some(bar) = self.bar {
+            bar.advance(count as u64);
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0.0), "0:00");
+        assert_eq!(format_duration(75.5), "1:15");
+        assert_eq!(format_duration(3725.0), "1:02:05");
+    }
+
+    #[test]
+    fn test_reader_counts_bytes() {
+        let bar = ProgressBar::for_files(2, 20);
+        for _ in 0..2 {
+            let mut contents = Vec::new();
+            let mut reader = CountingReader::new(&b"0123456789"[..], Some(&bar));
+            reader.read_to_end(&mut contents).unwrap();
+            assert_eq!(reader.count(), 10);
+            bar.file_done();
+        }
+
+        assert_eq!(bar.done_bytes.load(Ordering::Relaxed), 20);
+        assert_eq!(bar.done_files.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_modes() {
+        assert!(ProgressMode::Always.enabled(0));
+        assert!(!ProgressMode::Never.enabled(u64::MAX));
+        assert!(!ProgressMode::Auto.enabled(0));
+    }
+}
@@ -0,0 +1,193 @@
+//! A runtime registry of algorithms, looked up by name, which other algorithms can be added to.
+//!
+//! ```
+//! use steadyhash::{HashOptions, registry::Registry};
+//!
+//! let registry = Registry::builtin();
+//! let sha3 = registry.get("sha3").unwrap();
+//! let mut hasher = sha3.hasher(&HashOptions {
+//!     bit_length: 256,
+//!     ..Default::default()
+//! })?;
+//! hasher.update(b"abc");
+//! assert_eq!(
+//!     hasher.finalize(),
+//!     "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+//! );
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::{Checksum, HashOptions, errors::RegistryError, hashing::DynHasher};
+use anyhow::Error;
+
+/// Creates a hasher from the parameters of a checksum.
+pub type Constructor = Box<dyn Fn(&HashOptions) -> Result<Box<dyn DynHasher>, Error> + Send + Sync>;
+
+/// An algorithm in a [`Registry`].
+pub struct Algorithm {
+    /// Names the algorithm can be selected by, case-insensitively. The first one is its main name
+    pub names: Vec<String>,
+
+    /// Supported bit lengths. An empty list means any non-zero multiple of 8
+    pub bit_lengths: Vec<usize>,
+
+    /// Bit length used when none is given
+    pub default_bits: usize,
+
+    /// The built-in algorithm, if it's one
+    pub checksum: Option<Checksum>,
+
+    constructor: Constructor,
+}
+
+impl Algorithm {
+    /// Main name of the algorithm.
+    pub fn name(&self) -> &str {
+        &self.names[0]
+    }
+
+    /// Whether the algorithm can produce checksums of `bit_length` bits.
+    pub fn supports_bit_length(&self, bit_length: usize) -> bool {
+        if self.bit_lengths.is_empty() {
+            bit_length > 0 && bit_length.is_multiple_of(8)
+        } else {
+            self.bit_lengths.contains(&bit_length)
+        }
+    }
+
+    /// Creates a hasher with the parameters in `options`. A bit length of 0 selects the default
+    /// one.
+    pub fn hasher(&self, options: &HashOptions) -> Result<Box<dyn DynHasher>, Error> {
+        if options.bit_length == 0 {
+            return (self.constructor)(&HashOptions {
+                bit_length: self.default_bits,
+                ..options.clone()
+            });
+        }
+
+        (self.constructor)(options)
+    }
+}
+
+/// A set of algorithms, keyed by name.
+#[derive(Default)]
+pub struct Registry {
+    algorithms: Vec<Algorithm>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Creates a registry of every built-in algorithm.
+    pub fn builtin() -> Registry {
+        let mut registry = Registry::new();
+        for &checksum in Checksum::ALL {
+            registry.algorithms.push(Algorithm {
+                names: checksum.names().iter().map(|&name| name.into()).collect(),
+                bit_lengths: checksum.bit_lengths().to_vec(),
+                default_bits: checksum.default_bits(),
+                checksum: Some(checksum),
+                constructor: Box::new(move |options| checksum.hasher(options)),
+            });
+        }
+
+        registry
+    }
+
+    /// Adds an algorithm called `name`, whose hashers are created by `constructor`. Fails if the
+    /// name is already taken.
+    pub fn register(
+        &mut self,
+        name: &str,
+        bit_lengths: &[usize],
+        default_bits: usize,
+        constructor: Constructor,
+    ) -> Result<(), RegistryError> {
+        if self.get(name).is_some() {
+            return Err(RegistryError::DuplicateName(name.into()));
+        }
+
+        self.algorithms.push(Algorithm {
+            names: vec![name.into()],
+            bit_lengths: bit_lengths.to_vec(),
+            default_bits,
+            checksum: None,
+            constructor,
+        });
+        Ok(())
+    }
+
+    /// Looks up an algorithm by any of its names.
+    pub fn get(&self, name: &str) -> Option<&Algorithm> {
+        self.algorithms.iter().find(|algorithm| {
+            algorithm
+                .names
+                .iter()
+                .any(|other| other.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Creates a hasher for the algorithm called `name`, producing checksums of `bit_length` bits
+    /// (0 for the default length).
+    pub fn hasher(&self, name: &str, bit_length: usize) -> Result<Box<dyn DynHasher>, Error> {
+        let algorithm = self
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownAlgorithm(name.into()))?;
+        algorithm.hasher(&HashOptions {
+            bit_length,
+            ..Default::default()
+        })
+    }
+
+    /// Every algorithm, in the order they were registered.
+    pub fn algorithms(&self) -> &[Algorithm] {
+        &self.algorithms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::{Hasher, crc::Crc32Sum};
+
+    #[test]
+    fn test_builtin() {
+        let registry = Registry::builtin();
+        assert_eq!(registry.algorithms().len(), Checksum::ALL.len());
+
+        let blake2b = registry.get("B2").unwrap();
+        assert_eq!(blake2b.name(), "blake2b");
+        assert!(blake2b.supports_bit_length(256));
+        assert!(!blake2b.supports_bit_length(520));
+        assert!(registry.get("shake128").unwrap().supports_bit_length(1024));
+        assert!(registry.hasher("sha", 7).is_err());
+        assert!(registry.hasher("nope", 0).is_err());
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = Registry::builtin();
+        registry
+            .register(
+                "crc32-copy",
+                Crc32Sum::VALID_VALUES,
+                32,
+                Box::new(|_| Ok(Box::new(Crc32Sum::new()))),
+            )
+            .unwrap();
+        assert!(
+            registry
+                .register("CRC", &[], 32, Box::new(|_| unreachable!()))
+                .is_err()
+        );
+
+        let mut copy = registry.hasher("crc32-copy", 0).unwrap();
+        let mut original = registry.hasher("crc32", 0).unwrap();
+        copy.update(b"data");
+        original.update(b"data");
+        assert_eq!(copy.finalize(), original.finalize());
+    }
+}
@@ -0,0 +1,123 @@
+//! The JSON report of check mode written with `--report-file`, listing the result of every line of
+//! the manifests, so that CI systems can point at exactly which files failed.
+//!
+//! The report is an object with a `files` array, written as the files are checked, followed by
+//! the `summary` of the run.
+
+use crate::{
+    json,
+    output::{self, AtomicFile},
+};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use steadyhash::manifest::Summary;
+
+/// The result of a line of a manifest.
+pub struct ReportEntry<'a> {
+    /// Manifest the line is in
+    pub manifest: &'a Path,
+
+    /// Number of the line, starting at 1
+    pub line: usize,
+
+    /// `ok`, `failed`, `unreadable`, `missing` or `malformed`
+    pub status: &'a str,
+
+    /// Path of the file, unless the line is malformed
+    pub path: Option<&'a str>,
+
+    /// Name of the algorithm, unless the line is malformed
+    pub algorithm: Option<&'a str>,
+
+    /// Checksum listed in the manifest, unless the line is malformed
+    pub expected: Option<&'a str>,
+
+    /// Checksum computed for the file, if it was read
+    pub actual: Option<&'a str>,
+}
+
+impl ReportEntry<'_> {
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<&str>| value.map_or_else(|| "null".to_owned(), json::string);
+        format!(
+            r#"{{"manifest":{},"line":{},"status":"{}","path":{},"algorithm":{},"expected":{},"actual":{}}}"#,
+            json::string(&self.manifest.to_string_lossy()),
+            self.line,
+            self.status,
+            optional(self.path),
+            optional(self.algorithm),
+            optional(self.expected),
+            optional(self.actual),
+        )
+    }
+}
+
+/// A report being written.
+pub struct Report {
+    file: AtomicFile,
+
+    /// Number of entries written so far
+    entries: usize,
+}
+
+impl Report {
+    pub fn create(path: PathBuf) -> io::Result<Report> {
+        let mut file = AtomicFile::create(path)?;
+        file.write_all(b"{\"files\":[")?;
+        Ok(Report { file, entries: 0 })
+    }
+
+    pub fn record(&mut self, entry: &ReportEntry) -> io::Result<()> {
+        let separator = if self.entries == 0 { "\n" } else { ",\n" };
+        self.entries += 1;
+        write!(self.file, "{separator}{}", entry.to_json())
+    }
+
+    /// Writes the summary, and moves the report to its path.
+    pub fn finish(mut self, summary: &Summary) -> io::Result<()> {
+        writeln!(
+            self.file,
+            "\n],\"summary\":{}}}",
+            output::summary_json(summary)
+        )?;
+        self.file.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_json() {
+        let entry = ReportEntry {
+            manifest: Path::new("SUMS"),
+            line: 3,
+            status: "failed",
+            path: Some("foo.bar"),
+            algorithm: Some("MD5"),
+            expected: Some("abcd"),
+            actual: Some("abce"),
+        };
+        assert_eq!(
+            entry.to_json(),
+            r#"{"manifest":"SUMS","line":3,"status":"failed","path":"foo.bar","algorithm":"MD5","expected":"abcd","actual":"abce"}"#
+        );
+
+        let malformed = ReportEntry {
+            status: "malformed",
+            path: None,
+            algorithm: None,
+            expected: None,
+            actual: None,
+            ..entry
+        };
+        assert!(
+            malformed
+                .to_json()
+                .ends_with(r#""path":null,"algorithm":null,"expected":null,"actual":null}"#)
+        );
+    }
+}
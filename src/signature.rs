@@ -0,0 +1,227 @@
+//! Verification of the minisign or OpenBSD signify signature of a manifest, with `--verify-sig`.
+//!
+//! Both tools write Ed25519 keys and signatures as a line of base64 after an untrusted comment.
+//! Keys hold the algorithm, an 8-byte key number and the public key, and signatures hold the
+//! algorithm, the key number of the signing key and the signature. With the `ED` algorithm,
+//! minisign signs the BLAKE2b-512 hash of the file instead of the file itself, and its signature
+//! files add a trusted comment, whose global signature covers the signature and the comment.
+
+use anyhow::{Context, Error};
+use ed25519_dalek::{Signature as Ed25519Signature, Verifier, VerifyingKey};
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+use steadyhash::errors::SignatureError;
+
+const UNTRUSTED_COMMENT: &str = "untrusted comment:";
+const TRUSTED_COMMENT: &str = "trusted comment: ";
+
+/// A minisign or signify public key.
+pub struct PublicKey {
+    keynum: [u8; 8],
+    key: VerifyingKey,
+}
+
+impl PublicKey {
+    /// Parses the contents of a public key file, or the base64 line of the key alone.
+    pub fn parse(text: &str) -> Result<PublicKey, SignatureError> {
+        let bytes = base64_lines(text)
+            .next()
+            .ok_or(SignatureError::InvalidKey)?;
+        let bytes: [u8; 42] = bytes.try_into().map_err(|_| SignatureError::InvalidKey)?;
+        if &bytes[..2] != b"Ed" {
+            return Err(SignatureError::InvalidKey);
+        }
+
+        let key = VerifyingKey::from_bytes(bytes[10..].try_into().unwrap())
+            .map_err(|_| SignatureError::InvalidKey)?;
+        Ok(PublicKey {
+            keynum: bytes[2..10].try_into().unwrap(),
+            key,
+        })
+    }
+}
+
+/// A minisign or signify signature.
+pub struct Signature {
+    /// Whether the BLAKE2b-512 hash of the file was signed, rather than the file itself
+    prehashed: bool,
+    keynum: [u8; 8],
+    signature: Ed25519Signature,
+
+    /// The trusted comment of minisign and its global signature
+    trusted_comment: Option<(String, Ed25519Signature)>,
+}
+
+impl Signature {
+    /// Parses the contents of a signature file, or returns `None` if it isn't a signature.
+    pub fn parse(text: &str) -> Option<Signature> {
+        let mut lines = text
+            .lines()
+            .filter(|line| !line.starts_with(UNTRUSTED_COMMENT));
+        let bytes = data_encoding::BASE64
+            .decode(lines.next()?.trim().as_bytes())
+            .ok()?;
+        let bytes: [u8; 74] = bytes.try_into().ok()?;
+        let prehashed = match &bytes[..2] {
+            b"Ed" => false,
+            b"ED" => true,
+            _ => return None,
+        };
+
+        let trusted_comment = match lines.next() {
+            Some(line) => {
+                let comment = line.strip_prefix(TRUSTED_COMMENT)?;
+                let global = data_encoding::BASE64
+                    .decode(lines.next()?.trim().as_bytes())
+                    .ok()?;
+                let global = Ed25519Signature::from_slice(&global).ok()?;
+                Some((comment.to_owned(), global))
+            }
+            None => None,
+        };
+
+        Some(Signature {
+            prehashed,
+            keynum: bytes[2..10].try_into().unwrap(),
+            signature: Ed25519Signature::from_bytes(bytes[10..].try_into().unwrap()),
+            trusted_comment,
+        })
+    }
+
+    /// Verifies that `reader`, the contents of the manifest at `path`, were signed with `key`.
+    pub fn verify(&self, key: &PublicKey, mut reader: impl Read, path: &str) -> Result<(), Error> {
+        if self.keynum != key.keynum {
+            return Err(SignatureError::KeyMismatch {
+                expected: key_id(key.keynum),
+                signed: key_id(self.keynum),
+            }
+            .into());
+        }
+
+        let message = if self.prehashed {
+            let mut state = blake2b_simd::State::new();
+            io::copy(&mut reader, &mut state)?;
+            state.finalize().as_bytes().to_vec()
+        } else {
+            let mut contents = Vec::new();
+            reader.read_to_end(&mut contents)?;
+            contents
+        };
+        let mismatch = || SignatureError::Mismatch(path.to_owned());
+        key.key
+            .verify(&message, &self.signature)
+            .map_err(|_| mismatch())?;
+
+        if let Some((comment, global)) = &self.trusted_comment {
+            let signed = [&self.signature.to_bytes(), comment.as_bytes()].concat();
+            key.key.verify(&signed, global).map_err(|_| mismatch())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the manifest at `manifest`, or stdin for `-`, and verifies its signature, read from the
+/// file `signature`, with `pubkey`, which is either a public key file or the base64 line of the
+/// key. Returns the contents which were verified, so that the manifest checked is the one signed
+/// even if the file is replaced in the meantime.
+pub fn read_verified(manifest: &Path, signature: &Path, pubkey: &str) -> Result<Vec<u8>, Error> {
+    let key = match fs::read_to_string(pubkey) {
+        Ok(text) => PublicKey::parse(&text)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => PublicKey::parse(pubkey)?,
+        Err(e) => return Err(Error::new(e).context(format!("failed to read public key {pubkey}"))),
+    };
+    let text = fs::read_to_string(signature)
+        .with_context(|| format!("failed to read signature {}", signature.display()))?;
+    let signature = Signature::parse(&text)
+        .ok_or_else(|| SignatureError::InvalidSignature(signature.display().to_string()))?;
+
+    let mut contents = Vec::new();
+    if manifest == Path::new("-") {
+        io::stdin().lock().read_to_end(&mut contents)
+    } else {
+        File::open(manifest).and_then(|mut file| file.read_to_end(&mut contents))
+    }
+    .with_context(|| format!("failed to read manifest {}", manifest.display()))?;
+    signature.verify(&key, &contents[..], &manifest.display().to_string())?;
+
+    Ok(contents)
+}
+
+/// Decodes the lines of `text` which aren't untrusted comments as base64, skipping blank lines.
+fn base64_lines(text: &str) -> impl Iterator<Item = Vec<u8>> + '_ {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(UNTRUSTED_COMMENT))
+        .filter_map(|line| data_encoding::BASE64.decode(line.as_bytes()).ok())
+}
+
+/// Formats a key number like minisign does, as uppercase hex of a little-endian integer.
+fn key_id(keynum: [u8; 8]) -> String {
+    format!("{:016X}", u64::from_le_bytes(keynum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const KEYNUM: [u8; 8] = *b"steadyha";
+
+    fn encode(parts: &[&[u8]]) -> String {
+        data_encoding::BASE64.encode(&parts.concat())
+    }
+
+    #[test]
+    fn test_verify() {
+        let signing = SigningKey::from_bytes(&[7; 32]);
+        let pubkey = format!(
+            "untrusted comment: minisign public key\n{}\n",
+            encode(&[b"Ed", &KEYNUM, signing.verifying_key().as_bytes()])
+        );
+        let key = PublicKey::parse(&pubkey).unwrap();
+        let manifest = b"d41d8cd98f00b204e9800998ecf8427e  empty\n";
+
+        // signify signs the manifest itself
+        let signed = signing.sign(manifest).to_bytes();
+        let sig = format!(
+            "untrusted comment: verify with key.pub\n{}\n",
+            encode(&[b"Ed", &KEYNUM, &signed])
+        );
+        let sig = Signature::parse(&sig).unwrap();
+        assert!(sig.verify(&key, &manifest[..], "SUMS").is_ok());
+        assert!(sig.verify(&key, &b"tampered"[..], "SUMS").is_err());
+
+        // minisign signs its BLAKE2b-512 hash, and the trusted comment
+        let signed = signing
+            .sign(blake2b_simd::blake2b(manifest).as_bytes())
+            .to_bytes();
+        let global = signing.sign(&[&signed[..], b"file:SUMS"].concat());
+        let minisig = |comment: &str| {
+            format!(
+                "untrusted comment: signature\n{}\ntrusted comment: {comment}\n{}\n",
+                encode(&[b"ED", &KEYNUM, &signed]),
+                encode(&[&global.to_bytes()])
+            )
+        };
+        let sig = Signature::parse(&minisig("file:SUMS")).unwrap();
+        assert!(sig.verify(&key, &manifest[..], "SUMS").is_ok());
+        let sig = Signature::parse(&minisig("file:OTHER")).unwrap();
+        assert!(sig.verify(&key, &manifest[..], "SUMS").is_err());
+
+        let other = SigningKey::from_bytes(&[8; 32]);
+        let other = PublicKey::parse(&encode(&[
+            b"Ed",
+            b"otherkey",
+            other.verifying_key().as_bytes(),
+        ]))
+        .unwrap();
+        let err = sig.verify(&other, &manifest[..], "SUMS").unwrap_err();
+        assert!(err.to_string().contains("signed with the key"));
+        assert!(PublicKey::parse("not a key").is_err());
+        assert!(Signature::parse("untrusted comment: nothing\n").is_none());
+    }
+}
@@ -0,0 +1,153 @@
+//! Throughput statistics, reported at the end of a run with `--stats`.
+
+use crate::{json, units::format_size};
+use clap::ValueEnum;
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+/// How the statistics are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// Human-readable text
+    Text,
+
+    /// A single JSON object
+    Json,
+}
+
+/// Totals for one algorithm, or for several ones computed from a single read of the files, which
+/// are named together like `MD5+SHA1` as their time can't be told apart.
+struct AlgorithmStats {
+    name: String,
+    files: usize,
+    bytes: u64,
+
+    /// Time spent hashing, summed over every file. With several jobs, this can be longer than the
+    /// wall time of the run.
+    time: Duration,
+}
+
+/// Totals of a run.
+pub struct Stats {
+    start: Instant,
+
+    /// Totals by algorithm, in the order the algorithms were first used
+    algorithms: Vec<AlgorithmStats>,
+}
+
+impl Stats {
+    /// Starts measuring a run.
+    pub fn new() -> Stats {
+        Stats {
+            start: Instant::now(),
+            algorithms: Vec::new(),
+        }
+    }
+
+    /// Records that a file of `bytes` bytes was hashed with `algorithm`, or the algorithms named
+    /// together as `algorithm`, in `time`.
+    pub fn record(&mut self, algorithm: &str, bytes: u64, time: Duration) {
+        let index = match self.algorithms.iter().position(|a| a.name == algorithm) {
+            Some(index) => index,
+            None => {
+                self.algorithms.push(AlgorithmStats {
+                    name: algorithm.to_owned(),
+                    files: 0,
+                    bytes: 0,
+                    time: Duration::ZERO,
+                });
+                self.algorithms.len() - 1
+            }
+        };
+
+        let entry = &mut self.algorithms[index];
+        entry.files += 1;
+        entry.bytes += bytes;
+        entry.time += time;
+    }
+
+    /// Formats the totals of the run so far.
+    pub fn report(&self, format: StatsFormat) -> String {
+        let wall_time = self.start.elapsed();
+        let files: usize = self.algorithms.iter().map(|a| a.files).sum();
+        let bytes: u64 = self.algorithms.iter().map(|a| a.bytes).sum();
+
+        match format {
+            StatsFormat::Text => {
+                let mut report = format!(
+                    "files hashed: {files}\n\
+                     bytes processed: {} ({bytes} bytes)\n\
+                     wall time: {:.3}s\n\
+                     throughput: {}/s\n",
+                    format_size(bytes),
+                    wall_time.as_secs_f64(),
+                    format_size(rate(bytes, wall_time) as u64),
+                );
+                for a in &self.algorithms {
+                    let _ = writeln!(
+                        report,
+                        "  {}: {} files, {} in {:.3}s ({}/s)",
+                        a.name,
+                        a.files,
+                        format_size(a.bytes),
+                        a.time.as_secs_f64(),
+                        format_size(rate(a.bytes, a.time) as u64),
+                    );
+                }
+                report
+            }
+            StatsFormat::Json => {
+                let algorithms: Vec<String> = self
+                    .algorithms
+                    .iter()
+                    .map(|a| {
+                        format!(
+                            r#"{{"name":"{}","files":{},"bytes":{},"time_secs":{:.6},"bytes_per_sec":{:.0}}}"#,
+                            json::escape(&a.name),
+                            a.files,
+                            a.bytes,
+                            a.time.as_secs_f64(),
+                            rate(a.bytes, a.time),
+                        )
+                    })
+                    .collect();
+
+                format!(
+                    r#"{{"files":{files},"bytes":{bytes},"wall_time_secs":{:.6},"bytes_per_sec":{:.0},"algorithms":[{}]}}"#,
+                    wall_time.as_secs_f64(),
+                    rate(bytes, wall_time),
+                    algorithms.join(","),
+                ) + "\n"
+            }
+        }
+    }
+}
+
+/// Bytes per second, or 0 if no time was measured.
+fn rate(bytes: u64, time: Duration) -> f64 {
+    match time.as_secs_f64() {
+        0.0 => 0.0,
+        secs => bytes as f64 / secs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut stats = Stats::new();
+        stats.record("SHA256", 100, Duration::from_secs(1));
+        stats.record("MD5", 50, Duration::from_secs(1));
+        stats.record("SHA256", 300, Duration::from_secs(1));
+
+        let report = stats.report(StatsFormat::Json);
+        assert!(report.starts_with(r#"{"files":3,"bytes":450,"#));
+        assert!(report.contains(
+            r#"{"name":"SHA256","files":2,"bytes":400,"time_secs":2.000000,"bytes_per_sec":200}"#
+        ));
+    }
+}
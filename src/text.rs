@@ -0,0 +1,84 @@
+//! Text mode, with `--text`: CRLF line endings are read as LF, so that text files have the same
+//! checksums whichever platform they were written on.
+
+use std::io::{self, BufRead, BufReader, Read};
+
+/// A reader that replaces each CRLF of its inner reader with a LF. Lone CRs are kept.
+pub struct TextReader<R> {
+    inner: BufReader<R>,
+}
+
+impl<R: Read> TextReader<R> {
+    pub fn new(inner: R, buffer_size: usize) -> TextReader<R> {
+        TextReader {
+            inner: BufReader::with_capacity(buffer_size, inner),
+        }
+    }
+}
+
+impl<R: Read> Read for TextReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let mut written = 0;
+
+        // only block on the inner reader if nothing was read yet
+        while written < out.len() && (written == 0 || !self.inner.buffer().is_empty()) {
+            let available = self.inner.fill_buf()?;
+            let Some(&first) = available.first() else {
+                break;
+            };
+
+            if first != b'\r' {
+                let room = out.len() - written;
+                let n = available
+                    .iter()
+                    .take(room)
+                    .position(|&byte| byte == b'\r')
+                    .unwrap_or(available.len().min(room));
+                out[written..written + n].copy_from_slice(&available[..n]);
+                written += n;
+                self.inner.consume(n);
+                continue;
+            }
+
+            // the CR is dropped if a LF follows it, even in the next read of the inner reader
+            self.inner.consume(1);
+            if self.inner.fill_buf()?.first() != Some(&b'\n') {
+                out[written] = b'\r';
+                written += 1;
+            }
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_text(data: &[u8], buffer_size: usize, read_size: usize) -> Vec<u8> {
+        let mut reader = TextReader::new(data, buffer_size);
+        let mut text = Vec::new();
+        let mut buf = vec![0; read_size];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                return text;
+            }
+            text.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    #[test]
+    fn test_crlf_to_lf() {
+        let data = b"a\r\nb\rc\r\r\n\r";
+        // CRs at the end of the buffer or of a read must be handled too
+        for (buffer_size, read_size) in [(64, 64), (1, 1), (2, 3), (3, 2)] {
+            assert_eq!(
+                read_text(data, buffer_size, read_size),
+                b"a\nb\rc\r\n\r",
+                "{buffer_size} {read_size}"
+            );
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Limiting how fast files are read, with `--limit-rate`.
+
+use std::{
+    io::{self, Read},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A token bucket shared by every file read in a run, so that the limit applies to the whole
+/// process regardless of the number of jobs.
+///
+/// Reads always go through and are paid for afterwards: when the bucket runs dry, the reader
+/// sleeps until the bytes it read would have been allowed.
+pub struct RateLimiter {
+    /// Bytes allowed per second
+    rate: f64,
+
+    /// Maximum number of bytes that can be read in a burst, after being idle
+    burst: f64,
+
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    /// Bytes that can be read right away. It's negative when reads have to wait.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `rate` bytes per second, with bursts of up to a tenth of a
+    /// second's worth of data.
+    pub fn new(rate: u64) -> RateLimiter {
+        let rate = rate as f64;
+        let burst = rate / 10.0;
+
+        RateLimiter {
+            rate,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes `bytes` from the bucket, sleeping if they went over the limit.
+    pub fn consume(&self, bytes: usize) {
+        let wait = {
+            let Ok(mut bucket) = self.bucket.lock() else {
+                return;
+            };
+
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst) - bytes as f64;
+            bucket.last_refill = now;
+
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        };
+
+        thread::sleep(wait);
+    }
+}
+
+/// A reader that goes through a [`RateLimiter`].
+pub struct ThrottledReader<'a, R> {
+    inner: R,
+    limiter: &'a RateLimiter,
+}
+
+impl<'a, R: Read> ThrottledReader<'a, R> {
+    pub fn new(inner: R, limiter: &'a RateLimiter) -> ThrottledReader<'a, R> {
+        ThrottledReader { inner, limiter }
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.limiter.consume(count);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_rate() {
+        // 1 MB/s, with a 100 KB burst
+        let limiter = RateLimiter::new(1_000_000);
+        let data = vec![0u8; 300_000];
+
+        let start = Instant::now();
+        let mut contents = Vec::new();
+        ThrottledReader::new(data.as_slice(), &limiter)
+            .read_to_end(&mut contents)
+            .unwrap();
+
+        // the 200 KB past the burst take 200 ms
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        assert_eq!(contents.len(), data.len());
+    }
+}
@@ -0,0 +1,136 @@
+//! The `tree-hash` subcommand, which computes a single checksum for a whole directory.
+//!
+//! The checksum is the one of a listing of the files under the directory, sorted by their paths
+//! relative to it, written with `/` separators. Each file adds its kind (`F`), the checksum of
+//! its contents, its octal permissions with `--permissions`, and its path, each followed by a NUL.
+//! With `--symlinks`, symlinks add their kind (`L`) and target instead of a checksum. Empty
+//! directories aren't part of the listing.
+
+use crate::files::{self, WalkOptions};
+use anyhow::{Context, Error};
+use std::{
+    fs::{self, File, Metadata},
+    path::{Path, PathBuf},
+};
+use steadyhash::{Checksum, HashOptions, hash_reader, hashing::Input};
+
+/// Computes the checksum of the tree under `dir`, walked with `walk`, which keeps the symlinks
+/// with `--symlinks`. The files and the listing are hashed with `checksum`, and the permissions of
+/// the files are covered with `permissions`.
+pub fn tree_hash(
+    checksum: Checksum,
+    options: &HashOptions,
+    dir: &Path,
+    walk: &WalkOptions,
+    permissions: bool,
+) -> Result<String, Error> {
+    // anything else than a directory would be listed as a single file without a path
+    fs::read_dir(dir).with_context(|| format!("failed to read directory {}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = files::expand(vec![dir.to_owned()], walk)?
+        .iter()
+        .map(|path| path.strip_prefix(dir).unwrap_or(path).to_owned())
+        .collect();
+    files::sort(&mut paths);
+
+    let mut listing = Vec::new();
+    for relative in &paths {
+        let path = dir.join(relative);
+        let metadata = fs::symlink_metadata(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        let (kind, contents) = if metadata.is_symlink() {
+            let target = fs::read_link(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            ("L", target.to_string_lossy().into_owned())
+        } else {
+            let file =
+                File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+            let digest = hash_reader(checksum, options, file)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            ("F", digest)
+        };
+
+        let mut fields = vec![kind.to_owned(), contents];
+        if permissions {
+            fields.push(format!("{:o}", mode(&metadata)));
+        }
+        fields.push(relative.to_string_lossy().into_owned());
+        for field in fields {
+            listing.extend_from_slice(field.as_bytes());
+            listing.push(b'\0');
+        }
+    }
+
+    checksum.calculate(options, Input::Bytes(&listing))
+}
+
+/// The permission bits of a file. Other platforms than Unix only tell whether it's read-only.
+fn mode(metadata: &Metadata) -> u32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o7777
+    }
+    #[cfg(not(unix))]
+    {
+        if metadata.permissions().readonly() {
+            0o444
+        } else {
+            0o644
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    #[test]
+    fn test_tree_hash() {
+        let root = env::temp_dir().join(format!("steadyhash-tree-{}", process::id()));
+        let [a, b] = ["a", "b"].map(|name| root.join(name));
+        for dir in [&a, &b] {
+            fs::create_dir_all(dir.join("sub")).unwrap();
+            fs::write(dir.join("file"), "contents").unwrap();
+            fs::write(dir.join("sub/other"), "other").unwrap();
+        }
+        let options = HashOptions {
+            bit_length: 256,
+            ..Default::default()
+        };
+        let plain = WalkOptions::default();
+        let hash = |dir: &Path, walk: &WalkOptions| {
+            tree_hash(Checksum::Sha, &options, dir, walk, false).unwrap()
+        };
+
+        // the same tree in another place has the same checksum
+        assert_eq!(hash(&a, &plain), hash(&b, &plain));
+        fs::rename(b.join("sub/other"), b.join("sub/renamed")).unwrap();
+        assert_ne!(hash(&a, &plain), hash(&b, &plain));
+        fs::rename(b.join("sub/renamed"), b.join("sub/other")).unwrap();
+        fs::write(b.join("file"), "changed").unwrap();
+        assert_ne!(hash(&a, &plain), hash(&b, &plain));
+        fs::write(b.join("file"), "contents").unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let with_permissions =
+                |dir: &Path| tree_hash(Checksum::Sha, &options, dir, &plain, true).unwrap();
+            fs::set_permissions(b.join("file"), fs::Permissions::from_mode(0o600)).unwrap();
+            fs::set_permissions(a.join("file"), fs::Permissions::from_mode(0o644)).unwrap();
+            assert_eq!(hash(&a, &plain), hash(&b, &plain));
+            assert_ne!(with_permissions(&a), with_permissions(&b));
+
+            let symlinks = WalkOptions {
+                keep_symlinks: true,
+                ..Default::default()
+            };
+            std::os::unix::fs::symlink("file", b.join("link")).unwrap();
+            assert_eq!(hash(&a, &symlinks), hash(&b, &plain));
+            assert_ne!(hash(&a, &symlinks), hash(&b, &symlinks));
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,112 @@
+//! Parsing and formatting human-readable sizes.
+
+use steadyhash::errors::ParseSizeError;
+
+/// Multipliers for the supported suffixes. Like GNU coreutils, `K` and `KiB` are powers of 1024,
+/// while `KB` is a power of 1000.
+const SUFFIXES: &[(&str, u64)] = &[
+    ("", 1),
+    ("b", 1),
+    ("k", 1 << 10),
+    ("kib", 1 << 10),
+    ("kb", 1_000),
+    ("m", 1 << 20),
+    ("mib", 1 << 20),
+    ("mb", 1_000_000),
+    ("g", 1 << 30),
+    ("gib", 1 << 30),
+    ("gb", 1_000_000_000),
+    ("t", 1 << 40),
+    ("tib", 1 << 40),
+    ("tb", 1_000_000_000_000),
+];
+
+/// Parses a non-zero byte size, such as `4096`, `64K`, `4MiB` or `1GB`.
+pub fn parse_size(s: &str) -> Result<u64, ParseSizeError> {
+    let s = s.trim();
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, suffix) = s.split_at(digits);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| ParseSizeError::InvalidNumber(s.to_owned()))?;
+
+    let suffix = suffix.trim().to_ascii_lowercase();
+    let multiplier = SUFFIXES
+        .iter()
+        .find(|(name, _)| *name == suffix)
+        .map(|(_, multiplier)| *multiplier)
+        .ok_or_else(|| ParseSizeError::InvalidSuffix(s.to_owned()))?;
+
+    match number.checked_mul(multiplier) {
+        Some(0) => Err(ParseSizeError::Zero),
+        Some(size) => Ok(size),
+        None => Err(ParseSizeError::TooLarge(s.to_owned())),
+    }
+}
+
+/// Parses a non-zero rate in bytes per second, such as `50MiB/s`. The `/s` is optional.
+pub fn parse_rate(s: &str) -> Result<u64, ParseSizeError> {
+    let s = s.trim();
+    let size = s
+        .strip_suffix("/s")
+        .or_else(|| s.strip_suffix("/S"))
+        .unwrap_or(s);
+    parse_size(size)
+}
+
+/// Formats a byte count with a binary unit, e.g. `512 B` or `1.5 GiB`.
+pub fn format_size(size: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    if size < 1024 {
+        return format!("{size} B");
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        assert_eq!(parse_size("64K").unwrap(), 64 * 1024);
+        assert_eq!(parse_size("4MiB").unwrap(), 4 * 1024 * 1024);
+        assert_eq!(parse_size("1GB").unwrap(), 1_000_000_000);
+        assert_eq!(parse_size("2 mb").unwrap(), 2_000_000);
+    }
+
+    #[test]
+    fn test_invalid_sizes() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("0").is_err());
+        assert!(parse_size("MiB").is_err());
+        assert!(parse_size("4XB").is_err());
+        assert!(parse_size("99999999999T").is_err());
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("50MiB/s").unwrap(), 50 * 1024 * 1024);
+        assert_eq!(parse_rate("1000").unwrap(), 1000);
+        assert!(parse_rate("/s").is_err());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 << 30), "5.0 GiB");
+    }
+}
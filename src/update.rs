@@ -0,0 +1,253 @@
+//! Updating a manifest with `--update`: only the files which are new or whose size or modification
+//! time changed since the manifest was written are hashed again, and the entries of deleted files
+//! are dropped. Lines of another algorithm or style than the one of the update are written again
+//! too.
+//!
+//! The size of each file is recorded in a comment before its line, which check mode and coreutils
+//! skip, as its modification time may have been set back after it was modified.
+
+use crate::checkpoint::Resumed;
+use anyhow::Error;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, Metadata},
+    io::{BufRead, BufReader, ErrorKind},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use steadyhash::{
+    Checksum,
+    manifest::{self, DigestFormat},
+};
+
+/// Start of the comment recording the size of the file of the next line.
+const SIZE_COMMENT: &str = "# size: ";
+
+/// What to do to update a manifest.
+pub struct Update {
+    /// Files of the updated manifest, as they're written in it: those of the old one which still
+    /// exist, in the same order, followed by the new ones
+    pub files: Vec<PathBuf>,
+
+    /// Lines of the old manifest which are kept as they are, by path
+    pub unchanged: Resumed,
+
+    /// Paths the listed files are read from, when they're relative to another directory than the
+    /// current one, by the path written in the manifest
+    pub sources: HashMap<PathBuf, PathBuf>,
+}
+
+/// Plans the update of `manifest` to `checksum` checksums of `format`, written with the BSD-style
+/// `tag`, or in the GNU style without one. Its relative paths are resolved like in check mode,
+/// against `base_dir` if one is given. `files` are added if they aren't listed yet. A manifest
+/// which doesn't exist yet is created.
+pub fn plan(
+    manifest: &Path,
+    base_dir: Option<&Path>,
+    checksum: Checksum,
+    format: DigestFormat,
+    tag: Option<&str>,
+    files: &[PathBuf],
+) -> Result<Update, Error> {
+    let mut update = Update {
+        files: Vec::new(),
+        unchanged: Resumed::new(),
+        sources: HashMap::new(),
+    };
+    let mut listed = HashSet::new();
+
+    let file = match File::open(manifest) {
+        Ok(file) => Some(file),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    if let Some(file) = file {
+        let written = file.metadata()?.modified()?;
+        let mut size = None;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            // a recorded size only applies to the next line
+            let recorded = size.take();
+            if let Some(recorded) = line.strip_prefix(SIZE_COMMENT) {
+                size = recorded.parse::<u64>().ok();
+                continue;
+            }
+            let Some(entry) = manifest::parse_line(checksum, &line) else {
+                continue;
+            };
+            let path = PathBuf::from(&*entry.path);
+            let source = manifest::resolve_path(manifest, base_dir, &entry.path);
+            let metadata = match source.metadata() {
+                Ok(metadata) => Some(metadata),
+                Err(e) if e.kind() == ErrorKind::NotFound => {
+                    eprintln!(
+                        "warning: {}: dropping {}, which no longer exists",
+                        manifest.display(),
+                        path.display()
+                    );
+                    continue;
+                }
+                // hashing the file reports the error
+                Err(_) => None,
+            };
+            if !listed.insert(source.clone()) {
+                continue;
+            }
+
+            let current = entry.tag.as_deref() == tag
+                && manifest::parse_line_as(checksum, format, &line).is_some();
+            if let Some(metadata) = metadata
+                && current
+                && recorded.is_none_or(|size| size == metadata.len())
+                && !changed_since(&metadata, written)
+            {
+                update.unchanged.insert(
+                    path.display().to_string(),
+                    vec![with_size(&line, metadata.len())],
+                );
+            }
+            if source != path {
+                update.sources.insert(path.clone(), source);
+            }
+            update.files.push(path);
+        }
+    }
+
+    for file in files {
+        if listed.insert(file.clone()) {
+            update.files.push(file.clone());
+        }
+    }
+
+    Ok(update)
+}
+
+/// `line` of a manifest preceded by the comment recording the `size` of its file.
+pub fn with_size(line: &str, size: u64) -> String {
+    format!("{SIZE_COMMENT}{size}\n{line}")
+}
+
+/// Whether the file of `metadata` may have changed since `time`. On Unix, the time of the last
+/// change of its inode is compared too, as writing to or truncating the file updates it even if its
+/// modification time is set back, like `cp -p` or `touch -r` do. Like git does with racy entries, a
+/// file changed at `time` itself counts as changed, as it may have been written again within the
+/// resolution of the timestamps.
+fn changed_since(metadata: &Metadata, time: SystemTime) -> bool {
+    if metadata
+        .modified()
+        .map_or(true, |modified| modified >= time)
+    {
+        return true;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::{os::unix::fs::MetadataExt, time::Duration};
+        let changed = u64::try_from(metadata.ctime()).ok().and_then(|secs| {
+            let nanos = u32::try_from(metadata.ctime_nsec()).ok()?;
+            SystemTime::UNIX_EPOCH.checked_add(Duration::new(secs, nanos))
+        });
+        if changed.is_none_or(|changed| changed >= time) {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, process, thread, time::Duration};
+
+    #[test]
+    fn test_plan() {
+        let dir = env::temp_dir().join(format!("steadyhash-update-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let [kept, modified, resized, recorded, tagged, added, deleted] = [
+            "kept", "modified", "resized", "recorded", "tagged", "added", "deleted",
+        ]
+        .map(|name| dir.join(name).display().to_string());
+        for path in [&kept, &modified, &resized, &recorded, &tagged, &added] {
+            fs::write(path, "contents").unwrap();
+        }
+        let digest = "0123456789abcdef0123456789abcdef";
+        let manifest = dir.join("SUMS");
+        let mut lines = [&kept, &modified, &resized, &deleted, &kept]
+            .map(|path| format!("{digest}  {path}\n"))
+            .concat();
+        // the size recorded by a previous update tells a file of the same age apart
+        lines += &format!("{SIZE_COMMENT}3\n{digest}  {recorded}\n");
+        lines += &format!("SHA1 ({tagged}) = {}\n", "0".repeat(40));
+        // the manifest is written after the files changed
+        thread::sleep(Duration::from_millis(50));
+        fs::write(&manifest, lines).unwrap();
+        thread::sleep(Duration::from_millis(50));
+
+        let later = SystemTime::now() + Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&modified)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let earlier = SystemTime::now() - Duration::from_secs(60);
+        let file = File::options().write(true).open(&resized).unwrap();
+        file.set_len(3).unwrap();
+        file.set_modified(earlier).unwrap();
+
+        let md5 = DigestFormat {
+            encoding: Default::default(),
+            bit_length: Some(128),
+        };
+        let update = plan(
+            &manifest,
+            None,
+            Checksum::Md5,
+            md5,
+            None,
+            &[added.clone().into()],
+        )
+        .unwrap();
+        assert_eq!(
+            update.files,
+            [&kept, &modified, &resized, &recorded, &tagged, &added].map(PathBuf::from)
+        );
+        #[cfg(unix)]
+        assert_eq!(update.unchanged.len(), 1);
+        assert_eq!(
+            update.unchanged[&kept],
+            [with_size(&format!("{digest}  {kept}"), 8)]
+        );
+        assert!(update.sources.is_empty());
+
+        // lines of another algorithm or style are hashed again
+        let sha256 = DigestFormat {
+            bit_length: Some(256),
+            ..md5
+        };
+        let update = plan(&manifest, None, Checksum::Sha, sha256, None, &[]).unwrap();
+        assert!(update.unchanged.is_empty());
+        let update = plan(&manifest, None, Checksum::Md5, md5, Some("MD5"), &[]).unwrap();
+        assert!(update.unchanged.is_empty());
+
+        // relative paths are read from the directory of the manifest, but written as they are
+        fs::write(&manifest, format!("{digest}  kept\n")).unwrap();
+        let update = plan(&manifest, None, Checksum::Md5, md5, None, &[]).unwrap();
+        assert_eq!(update.files, [PathBuf::from("kept")]);
+        assert_eq!(update.sources[Path::new("kept")], Path::new(&kept));
+        let update = plan(&manifest, Some(&dir), Checksum::Md5, md5, None, &[]).unwrap();
+        assert_eq!(update.sources[Path::new("kept")], Path::new(&kept));
+
+        let update = plan(
+            &dir.join("missing"),
+            None,
+            Checksum::Md5,
+            md5,
+            None,
+            &[added.into()],
+        );
+        assert_eq!(update.unwrap().files.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+//! JavaScript bindings, built with the `wasm` feature and `wasm-bindgen`.
+//!
+//! ```js
+//! import { hash, Hasher } from "steadyhash";
+//!
+//! const checksum = hash("sha", 256, bytes);
+//!
+//! const hasher = new Hasher("blake3", 0);
+//! hasher.update(firstChunk);
+//! hasher.update(secondChunk);
+//! const streamed = hasher.finalize();
+//! ```
+
+use crate::{HashOptions, errors::RegistryError, hashing::DynHasher, registry::Registry};
+use wasm_bindgen::prelude::*;
+
+/// An incremental hasher, for data that arrives in pieces, like a download being streamed.
+#[wasm_bindgen]
+pub struct Hasher {
+    hasher: Box<dyn DynHasher>,
+}
+
+#[wasm_bindgen]
+impl Hasher {
+    /// Creates a hasher for the algorithm called `name`, as accepted by `--type`. A `bit_length`
+    /// of 0 selects the default bit length of the algorithm, and `key` is the key of keyed
+    /// algorithms.
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: &str, bit_length: usize, key: Option<Vec<u8>>) -> Result<Hasher, JsError> {
+        let registry = Registry::builtin();
+        let algorithm = registry
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownAlgorithm(name.into()))?;
+        let hasher = algorithm
+            .hasher(&HashOptions {
+                bit_length,
+                key,
+                ..Default::default()
+            })
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(Hasher { hasher })
+    }
+
+    /// Feeds more data to the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    /// Consumes the hasher, returning the checksum as a hex string.
+    pub fn finalize(self) -> String {
+        self.hasher.finalize()
+    }
+}
+
+/// Hashes `data` at once with the algorithm called `name`, as with [`Hasher::new`].
+#[wasm_bindgen]
+pub fn hash(
+    name: &str,
+    bit_length: usize,
+    data: &[u8],
+    key: Option<Vec<u8>>,
+) -> Result<String, JsError> {
+    let mut hasher = Hasher::new(name, bit_length, key)?;
+    hasher.update(data);
+    Ok(hasher.finalize())
+}
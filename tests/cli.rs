@@ -0,0 +1,316 @@
+//! Runs the steadyhash binary, for the behavior which depends on its exit code and output.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{self, Command, Output},
+};
+
+/// A fresh directory for the files of the test `name`.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("steadyhash-cli-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn steadyhash() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_steadyhash"))
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_strict_malformed() {
+    let dir = test_dir("strict");
+    fs::write(dir.join("file"), "").unwrap();
+    fs::write(
+        dir.join("SUMS"),
+        "d41d8cd98f00b204e9800998ecf8427e  file\ngarbage line\n",
+    )
+    .unwrap();
+
+    let check = |options: &[&str]| {
+        steadyhash()
+            .args(["check", "-t", "md5"])
+            .args(options)
+            .arg(dir.join("SUMS"))
+            .output()
+            .unwrap()
+    };
+    // malformed lines only fail the check with --strict
+    assert!(check(&[]).status.success());
+    let output = check(&["--strict", "-w"]);
+    assert_eq!(output.status.code(), Some(1));
+    let warnings = stderr(&output);
+    assert!(warnings.contains("SUMS: 2: improperly formatted MD5 checksum line"));
+    assert!(warnings.contains("1 line is improperly formatted"));
+
+    // a manifest without a single entry fails even without --strict
+    fs::write(dir.join("SUMS"), "garbage\n").unwrap();
+    let output = check(&[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("SUMS: no properly formatted checksum lines found"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_multicall_check() {
+    use std::os::unix::process::CommandExt;
+
+    let dir = test_dir("multicall");
+    fs::write(dir.join("file"), "").unwrap();
+    let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    let sha256sum = |manifest: &str, options: &[&str]| {
+        fs::write(dir.join("SUMS"), manifest).unwrap();
+        steadyhash()
+            .arg0("sha256sum")
+            .arg("-c")
+            .args(options)
+            .arg(dir.join("SUMS"))
+            .output()
+            .unwrap()
+    };
+
+    let output = sha256sum(&format!("{empty}  file\n"), &["--status"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    let output = sha256sum(&format!("{}  file\n", "0".repeat(64)), &["--status"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+
+    let output = sha256sum(&format!("{empty}  file\n{empty}  gone\n"), &["--quiet"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("file: OK"));
+    let output = sha256sum(
+        &format!("{empty}  file\n{empty}  gone\nbad\n"),
+        &["--ignore-missing", "--strict", "-w"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("improperly formatted"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_report_missing() {
+    let dir = test_dir("report");
+    fs::write(dir.join("file"), "").unwrap();
+    let empty = "d41d8cd98f00b204e9800998ecf8427e";
+    fs::write(dir.join("SUMS"), format!("{empty}  file\n{empty}  gone\n")).unwrap();
+
+    let output = steadyhash()
+        .args(["check", "-t", "md5", "--report-file"])
+        .arg(dir.join("report.json"))
+        .arg(dir.join("SUMS"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("gone: FAILED open or read"));
+    let report = fs::read_to_string(dir.join("report.json")).unwrap();
+    assert!(report.contains(r#""line":2,"status":"missing","path":"gone""#));
+    assert!(report.contains(r#""summary":{"ok":1,"failed":0,"unreadable":0,"missing":1,"#));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_unknown_tag() {
+    let dir = test_dir("tag");
+    fs::write(dir.join("file"), "").unwrap();
+    fs::write(
+        dir.join("SUMS"),
+        "MD5 (file) = d41d8cd98f00b204e9800998ecf8427e\nNOPE (file) = 1234\n",
+    )
+    .unwrap();
+
+    let check = |options: &[&str]| {
+        steadyhash()
+            .arg("check")
+            .args(options)
+            .arg(dir.join("SUMS"))
+            .output()
+            .unwrap()
+    };
+    let output = check(&[]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("file: OK"));
+    assert!(stderr(&output).contains("1 line is improperly formatted"));
+    let output = check(&["--strict", "-w"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("SUMS: 2: improperly formatted checksum line"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_hash_unreadable() {
+    let dir = test_dir("unreadable");
+    fs::write(dir.join("file"), "").unwrap();
+
+    let output = steadyhash()
+        .args(["-t", "md5"])
+        .args([dir.join("nope"), dir.join("file")])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("nope: No such file or directory"));
+    // the other files are still hashed
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("d41d8cd98f00b204e9800998ecf8427e  "));
+    assert_eq!(stdout.lines().count(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_diff_exit_codes() {
+    let dir = test_dir("diff");
+    let digest = "d41d8cd98f00b204e9800998ecf8427e";
+    fs::write(dir.join("OLD"), format!("{digest}  a\n")).unwrap();
+    fs::write(dir.join("NEW"), format!("{digest}  a\n{digest}  b\n")).unwrap();
+
+    let diff = |old: &str, new: &str| {
+        steadyhash()
+            .args(["diff", "-t", "md5"])
+            .args([dir.join(old), dir.join(new)])
+            .output()
+            .unwrap()
+    };
+    assert_eq!(diff("OLD", "OLD").status.code(), Some(0));
+    let output = diff("OLD", "NEW");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "A\tb\n");
+    let output = diff("OLD", "nope");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("nope"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_manifest_round_trip() {
+    let dir = test_dir("round-trip");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("sub/b"), "").unwrap();
+    let run = |args: &[&str]| {
+        steadyhash()
+            .current_dir(&dir)
+            .args(["-t", "md5"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    // paths relative to the current directory, as hashing writes them
+    assert!(run(&["-o", "sub/SUMS", "sub/b"]).status.success());
+    let output = run(&["-c", "sub/SUMS"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "sub/b: OK\n");
+
+    // and relative to the directory of the manifest
+    fs::write(
+        dir.join("sub/SUMS"),
+        "d41d8cd98f00b204e9800998ecf8427e  b\n",
+    )
+    .unwrap();
+    assert!(run(&["-c", "sub/SUMS"]).status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_output_per_algorithm() {
+    let dir = test_dir("per-algorithm");
+    fs::write(dir.join("a"), "").unwrap();
+
+    let output = steadyhash()
+        .current_dir(&dir)
+        .args(["-t", "md5", "-t", "sha1", "-o", "SUMS.{algo}"])
+        .args(["--format", r"{hash}\n{file}", "a"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+    assert_eq!(read("SUMS.md5"), "d41d8cd98f00b204e9800998ecf8427e\na\n");
+    assert_eq!(
+        read("SUMS.sha1"),
+        "da39a3ee5e6b4b0d3255bfef95601890afd80709\na\n"
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_recursive_output() {
+    let dir = test_dir("recursive");
+    fs::write(dir.join("a"), "").unwrap();
+    let run = |args: &[&str]| {
+        steadyhash()
+            .current_dir(&dir)
+            .args(["-t", "md5"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    // neither the manifest being written nor the one of the previous run is listed
+    for _ in 0..2 {
+        assert!(run(&["-r", ".", "-o", "SUMS"]).status.success());
+        let manifest = fs::read_to_string(dir.join("SUMS")).unwrap();
+        assert_eq!(manifest, "d41d8cd98f00b204e9800998ecf8427e  ./a\n");
+    }
+    assert!(run(&["-c", "SUMS"]).status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_update_elsewhere() {
+    let dir = test_dir("update");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("sub/b"), "").unwrap();
+    let run = |args: &[&str]| {
+        steadyhash()
+            .current_dir(&dir)
+            .args(["-t", "md5"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let empty = "d41d8cd98f00b204e9800998ecf8427e";
+
+    // the paths of the manifest are relative to its directory, and are written as they were
+    fs::write(dir.join("sub/SUMS"), format!("{empty}  b\n{empty}  gone\n")).unwrap();
+    let output = run(&["--update", "sub/SUMS"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("dropping gone, which no longer exists"));
+    let manifest = fs::read_to_string(dir.join("sub/SUMS")).unwrap();
+    assert_eq!(manifest, format!("# size: 0\n{empty}  b\n"));
+
+    // or to --base-dir
+    fs::write(dir.join("SUMS"), format!("{empty}  b\n")).unwrap();
+    let output = run(&["--update", "SUMS", "--base-dir", "sub"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let manifest = fs::read_to_string(dir.join("SUMS")).unwrap();
+    assert_eq!(manifest, format!("# size: 0\n{empty}  b\n"));
+    assert!(run(&["-c", "SUMS", "--base-dir", "sub"]).status.success());
+    assert_eq!(run(&["sub/b", "--base-dir", "sub"]).status.code(), Some(2));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_tuplehash_limit() {
+    let dir = test_dir("tuplehash");
+    fs::write(dir.join("small"), "").unwrap();
+    // sparse, so it takes no space
+    fs::File::create(dir.join("large"))
+        .unwrap()
+        .set_len(2 << 30)
+        .unwrap();
+
+    let output = steadyhash()
+        .current_dir(&dir)
+        .args(["-t", "tuplehash128", "large", "small"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("large: TupleHash keeps its whole input in memory"));
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("  small\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}